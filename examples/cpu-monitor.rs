@@ -103,17 +103,17 @@ impl EventsHandler {
         &mut self,
         display: &GliumDisplayWinitWrapper,
         interface: &mut conrod::Ui,
-        mut events_loop: &mut glium::glutin::EventsLoop,
+        events_loop: &mut glium::glutin::EventsLoop,
     ) -> EventsHandlerOutcome {
-        for event in self.event_loop.next(&mut events_loop) {
+        for event in self.event_loop.next(events_loop) {
             // Use the `winit` backend feature to convert the winit event to a conrod one.
             if let Some(event) = convert_event(event.clone(), display) {
                 interface.handle_event(event);
             }
 
             // Break from the loop upon `Escape` or closed window.
-            if let glium::glutin::Event::WindowEvent { event, .. } = event.clone() {
-                match event {
+            if let glium::glutin::Event::WindowEvent {
+                event:
                     glium::glutin::WindowEvent::CloseRequested
                     | glium::glutin::WindowEvent::KeyboardInput {
                         input:
@@ -122,11 +122,11 @@ impl EventsHandler {
                                 ..
                             },
                         ..
-                    } => {
-                        return EventsHandlerOutcome::Break;
-                    }
-                    _ => (),
-                }
+                    },
+                ..
+            } = event
+            {
+                return EventsHandlerOutcome::Break;
             }
         }
 
@@ -206,18 +206,22 @@ fn main() {
     let mut conrod_graph = ConrodBackendReusableGraph::build();
 
     // Initialize common canvas style
-    let mut canvas_style = conrod::widget::canvas::Style::default();
+    let canvas_style = conrod::widget::canvas::Style {
+        border: Some(0.0),
+        border_color: Some(conrod::color::TRANSPARENT),
+        color: Some(conrod::color::TRANSPARENT),
 
-    canvas_style.border = Some(0.0);
-    canvas_style.border_color = Some(conrod::color::TRANSPARENT);
-    canvas_style.color = Some(conrod::color::TRANSPARENT);
+        ..Default::default()
+    };
 
     // Initialize common title text style
-    let mut title_text_style = conrod_core::widget::primitive::text::Style::default();
+    let title_text_style = conrod_core::widget::primitive::text::Style {
+        font_id: Some(Some(font_bold)),
+        color: Some(conrod::color::WHITE),
+        font_size: Some(TITLE_FONT_SIZE),
 
-    title_text_style.font_id = Some(Some(font_bold));
-    title_text_style.color = Some(conrod::color::WHITE);
-    title_text_style.font_size = Some(TITLE_FONT_SIZE);
+        ..Default::default()
+    };
 
     // Run events handler
     let mut events_handler = EventsHandler::run();
@@ -370,8 +374,8 @@ fn render_bitmap_plot(
     }
 }
 
-fn render_conrod_plot<'a, 'b>(
-    ui: &'a mut conrod::UiCell<'b>,
+fn render_conrod_plot<'b>(
+    ui: &mut conrod::UiCell<'b>,
     data_points: &mut VecDeque<(chrono::DateTime<chrono::Utc>, i32)>,
     ids: &'b Ids,
     font: conrod_core::text::font::Id,
@@ -396,14 +400,11 @@ fn plot<D: IntoDrawingArea>(
     // Acquire time range
     let newest_time = data_points
         .front()
-        .unwrap_or(&(
-            chrono::DateTime::from_utc(chrono::NaiveDateTime::from_timestamp(0, 0), chrono::Utc),
-            0,
-        ))
+        .unwrap_or(&(chrono::DateTime::from_timestamp(0, 0).unwrap(), 0))
         .0;
     let oldest_time = newest_time - chrono::Duration::seconds(PLOT_SECONDS as i64);
 
-    let mut chart = ChartBuilder::on(&drawing)
+    let mut chart = ChartBuilder::on(drawing)
         .x_label_area_size(0)
         .y_label_area_size(28)
         .margin(20)
@@ -412,8 +413,8 @@ fn plot<D: IntoDrawingArea>(
 
     chart
         .configure_mesh()
-        .bold_line_style(&plotters::style::colors::WHITE.mix(0.1))
-        .light_line_style(&plotters::style::colors::WHITE.mix(0.05))
+        .bold_line_style(plotters::style::colors::WHITE.mix(0.1))
+        .light_line_style(plotters::style::colors::WHITE.mix(0.05))
         .axis_style(ShapeStyle::from(&plotters::style::colors::WHITE.mix(0.45)).stroke_width(1))
         .y_labels(10)
         .y_label_style(
@@ -428,9 +429,9 @@ fn plot<D: IntoDrawingArea>(
     chart
         .draw_series(
             AreaSeries::new(
-                data_points.iter().map(|x| (x.0, x.1 as i32)),
+                data_points.iter().map(|x| (x.0, x.1)),
                 0,
-                &PLOT_LINE_COLOR.mix(0.175),
+                PLOT_LINE_COLOR.mix(0.175),
             )
             .border_style(ShapeStyle::from(&PLOT_LINE_COLOR).stroke_width(2)),
         )
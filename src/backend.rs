@@ -5,17 +5,37 @@
 // License: MIT
 
 use std::convert::From;
+use std::mem;
+use std::time::{Duration, Instant};
 
-use conrod_core::{self as conrod, position::Scalar as ConrodScalar, Positionable, Widget};
+use conrod_core::{
+    self as conrod, position::Scalar as ConrodScalar, Colorable, Positionable, Widget,
+};
 use plotters_backend::{
-    text_anchor, BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend,
-    DrawingErrorKind,
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontTransform,
 };
 
-use crate::error::ConrodBackendError;
+use crate::error::{ConrodBackendError, ConrodBackendErrorContext, ConrodBackendPrimitive};
+use crate::geometry::{
+    self, ConrodBackendGeometry, GeometryPoint, GeometryPrimitive, GeometryScratch,
+    GeometrySimplify,
+};
 use crate::graph::ConrodBackendReusableGraph;
-use crate::triangulate;
-use crate::utils::{color, convert, path, position, shape};
+use crate::hitmap::ConrodBackendHitmap;
+use crate::report::ConrodBackendDegradation;
+use crate::transform::ConrodBackendTransform;
+use crate::utils::{color, convert, position};
+
+const DEGRADATION_COARSE_BUDGET_RATIO: f64 = 1.25;
+const DEGRADATION_OUTLINE_BUDGET_RATIO: f64 = 1.5;
+const CIRCLE_COARSE_RESOLUTION: usize = 16;
+
+const DEBUG_OVERLAY_COLOR: conrod::color::Color = conrod::color::Color::Rgba(1.0, 0.0, 1.0, 0.8);
+const DEBUG_OVERLAY_THICKNESS: ConrodScalar = 1.0;
+const DEBUG_OVERLAY_ANCHOR_SIZE: ConrodScalar = 3.0;
+const DEBUG_OVERLAY_FONT_SIZE: conrod::FontSize = 10;
+const DEBUG_OVERLAY_MARGIN: ConrodScalar = 4.0;
 
 /// The Conrod drawing backend
 pub struct ConrodBackend<'a, 'b> {
@@ -24,6 +44,15 @@ pub struct ConrodBackend<'a, 'b> {
     parent: conrod::widget::Id,
     font: conrod::text::font::Id,
     graph: &'a mut ConrodBackendReusableGraph,
+    recording: Option<&'a mut ConrodBackendGeometry>,
+    hitmap: Option<&'a ConrodBackendHitmap>,
+    strict: bool,
+    simplify: GeometrySimplify,
+    stroke_polygons: bool,
+    budget: Option<Duration>,
+    started: Instant,
+    debug: bool,
+    transform: Option<ConrodBackendTransform>,
 }
 
 impl<'a, 'b> ConrodBackend<'a, 'b> {
@@ -51,8 +80,900 @@ impl<'a, 'b> ConrodBackend<'a, 'b> {
             font,
             size,
             graph,
+            recording: None,
+            hitmap: None,
+            strict: false,
+            simplify: GeometrySimplify::default(),
+            stroke_polygons: false,
+            budget: None,
+            started: Instant::now(),
+            debug: false,
+            transform: None,
+        }
+    }
+
+    /// Enable strict mode, where unsupported operations return an error instead of being silently ignored
+    ///
+    /// Unsupported operations are pixels, bitmaps and text transforms (eg. rotated texts). Whether strict mode is enabled or not, all unsupported operations are reported in the skip report, which can be acquired from the graph after drawing a frame (see `ConrodBackendReusableGraph::skipped()`).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+
+        self
+    }
+
+    /// Simplify dense geometry, by dropping points that deviate less than a tolerance from a straight line, with:
+    /// - `tolerance`: the maximum deviation in pixels (eg. `0.5` for sub-pixel error), `0.0` disables simplification
+    /// - `paths`: whether paths also get simplified (filled polygons always get simplified)
+    ///
+    /// This reduces the number of vertices sent to Conrod on dense series. Note that when paths get simplified, the vertex indices reported by the hitmap refer to the simplified paths.
+    pub fn simplify(mut self, tolerance: ConrodScalar, paths: bool) -> Self {
+        self.simplify.tolerance = tolerance;
+        self.simplify.paths = paths;
+
+        self
+    }
+
+    /// Decimate paths down to their envelope in each pixel column (ie. first, minimum, maximum and last points)
+    ///
+    /// This is a level-of-detail pass meant for very long paths (eg. 1M samples drawn on a 800 pixels wide plot), which cuts down the number of vertices sent to Conrod to at most 4 times the plot width, without any visible change. Note that when paths get decimated, the vertex indices reported by the hitmap refer to the decimated paths.
+    pub fn decimate(mut self, decimate: bool) -> Self {
+        self.simplify.decimate = decimate;
+
+        self
+    }
+
+    /// Stroke the outline of filled polygons on top of their fill, as a closed path (using the polygon style color and stroke width)
+    ///
+    /// Plotters only requests polygons to be filled, while some elements (eg. custom elements with a stroke style) also expect an outline. Note that paths ending on their starting point are always stroked as closed rings, with a join on the closing vertex.
+    pub fn stroke_polygons(mut self, stroke_polygons: bool) -> Self {
+        self.stroke_polygons = stroke_polygons;
+
+        self
+    }
+
+    /// Set a frame time budget, with:
+    /// - `budget`: the maximum time that drawing this frame should take, counted from the creation of the backend (eg. `Duration::from_millis(33)` at 30 FPS)
+    ///
+    /// Once the budget is exceeded mid-frame, the remaining primitives get rendered in a degraded way, in stages: paths are decimated first, then sub-pixel smoothing is dropped (at 125% of the budget; this stands in for disabling anti-aliasing, which Conrod does not expose any control for), then polygons are stroked as outlines instead of being filled (at 150% of the budget). The reached stage is reported in the render report, which can be acquired from the graph after drawing a frame (see `ConrodBackendReusableGraph::rendered()`).
+    pub fn budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+
+        self
+    }
+
+    /// Enable debug mode, where an overlay is drawn on top of the plot
+    ///
+    /// The overlay shows the estimated bounding box and anchor point of each text, the extents of rectangles and circles, and the wireframe of triangles used to fill polygons. A label with the counters of the frame (see `ConrodBackendReusableGraph::rendered()`) is also drawn in the corner of the parent. Note that the overlay gets drawn once the backend is dropped, and that it is not recorded.
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+
+        self
+    }
+
+    /// Apply a global affine transform to the plot, with:
+    /// - `transform`: the transform to apply to all plot coordinates (eg. `ConrodBackendTransform::rotate_quarters(1, (plot_width, plot_height))` to rotate the plot by 90°)
+    ///
+    /// The transform gets applied to every coordinate sent to Conrod, which means that rectangles become polygons when rotated. Circles get transformed as ovals, and texts get moved to their transformed anchor point (texts are not rotated, as Conrod does not support it). Stroke widths and font sizes are not scaled. Note that the mouse position acquired from `ConrodBackend::mouse_position()` can be mapped back to backend coordinates using `ConrodBackendTransform::invert()`, and that recorded geometry is kept untransformed.
+    pub fn transform(mut self, transform: ConrodBackendTransform) -> Self {
+        self.transform = Some(transform);
+
+        self
+    }
+
+    /// Record all primitives drawn in this frame into a geometry container, with:
+    /// - `geometry`: a mutable reference to the geometry container to record into (any previous content gets cleared)
+    ///
+    /// The recorded primitives are the exact same ones that get sent to Conrod (ie. after path simplification and triangulation), which means that they can be exported (eg. to SVG) and match what is rendered on screen.
+    pub fn record_into(mut self, geometry: &'a mut ConrodBackendGeometry) -> Self {
+        geometry.prepare(self.size);
+
+        self.recording = Some(geometry);
+
+        self
+    }
+
+    /// Index all primitives drawn in this frame into a hitmap, with:
+    /// - `hitmap`: a reference to the hitmap to index into (any previous content gets cleared)
+    ///
+    /// The hitmap can then be queried for the nearest primitive to a given position (eg. the mouse position), which can be used to show tooltips or select series elements.
+    pub fn hit_test_into(mut self, hitmap: &'a ConrodBackendHitmap) -> Self {
+        hitmap.prepare(self.size);
+
+        self.hitmap = Some(hitmap);
+
+        self
+    }
+
+    /// Acquire the current mouse position relative to the plot, with:
+    /// - `ui`: the `Ui` (or `UiCell`) for this frame
+    /// - `ids.parent`: the `widget::Id` of the canvas that contains your plot
+    ///
+    /// The position is returned in backend pixel coordinates (ie. the same coordinates space than the one used by Plotters), or `None` if the mouse is outside of the plot. It can be combined with the coordinate translator obtained from `ChartContext::into_coord_trans()` as to obtain data coordinates.
+    pub fn mouse_position(ui: &conrod::Ui, parent: conrod::widget::Id) -> Option<BackendCoord> {
+        let (parent_rect, mouse_xy) = (ui.rect_of(parent)?, ui.global_input().current.mouse.xy);
+
+        // Only return mouse position if it is hovering the parent
+        if parent_rect.is_over(mouse_xy) {
+            position::PositionParent::from(ui, parent)
+                .map(|position| position.rel_point_backend_coord(&mouse_xy))
+        } else {
+            None
+        }
+    }
+
+    /// Draw a geometry that was prepared beforehand using a `ConrodBackendGeometryBuilder`
+    ///
+    /// This only instantiates Conrod widgets, as all expensive geometry processing was already \
+    ///   done when building the geometry. It is thus suitable for building the geometry on a \
+    ///   worker thread, and submitting it to Conrod from the UI thread.
+    pub fn draw_geometry(
+        &mut self,
+        geometry: &ConrodBackendGeometry,
+    ) -> Result<(), ConrodBackendError> {
+        // Carry over operations that were skipped while building the geometry
+        self.graph.skipped.merge(&geometry.skipped);
+
+        // Acquire absolute position generator (in parent container)
+        let position = match (self.position(), geometry.primitives.first()) {
+            (Some(position), _) => position,
+            (None, Some(primitive)) => {
+                return Err(ConrodBackendError::NoParentPosition {
+                    context: self.error_context(primitive.kind(), primitive.points()),
+                })
+            }
+            (None, None) => return Ok(()),
+        };
+
+        for primitive in geometry.primitives.iter() {
+            self.graph.rendered.count(primitive.kind());
+
+            match primitive {
+                GeometryPrimitive::Line {
+                    from,
+                    to,
+                    color,
+                    thickness,
+                } => self.set_line(&position, from, to, *color, *thickness),
+                GeometryPrimitive::Rect {
+                    upper_left,
+                    size,
+                    color,
+                    thickness,
+                    fill,
+                } => self.set_rect(&position, upper_left, size, *color, *thickness, *fill),
+                GeometryPrimitive::Path {
+                    points,
+                    color,
+                    thickness,
+                } => self.set_path(&position, points, *color, *thickness),
+                GeometryPrimitive::Circle {
+                    center,
+                    radius,
+                    color,
+                    thickness,
+                    fill,
+                    resolution,
+                } => self.set_circle(
+                    &position,
+                    center,
+                    *radius,
+                    Self::shape_style(*color, *thickness, *fill),
+                    *resolution,
+                ),
+                GeometryPrimitive::Fill { triangles, color } => {
+                    for triangle in triangles.iter() {
+                        self.batch_triangle(&position, triangle, *color);
+
+                        self.overlay_outline(triangle, true, false);
+                    }
+                }
+                GeometryPrimitive::Text {
+                    text,
+                    pos,
+                    size,
+                    color,
+                    justify,
+                    family,
+                    ..
+                } => {
+                    self.check_font(family)?;
+                    self.set_text(&position, text, pos, *size, *color, *justify)
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn position(&self) -> Option<position::PositionParent> {
+        // Acquire absolute position generator (in parent container, with the global transform)
+        position::PositionParent::from(self.ui, self.parent)
+            .map(|position| position.with_transform(self.transform))
+    }
+
+    #[inline(always)]
+    fn error_context(
+        &self,
+        primitive: ConrodBackendPrimitive,
+        points: usize,
+    ) -> ConrodBackendErrorContext {
+        ConrodBackendErrorContext::new(primitive, Some(self.parent), points)
+    }
+
+    #[inline(always)]
+    fn check_font(&self, family: &str) -> Result<(), ConrodBackendError> {
+        // Ensure that the font is known to Conrod (otherwise, Conrod would panic when rendering \
+        //   text widgets)
+        if self.ui.fonts.get(self.font).is_some() {
+            Ok(())
+        } else {
+            Err(ConrodBackendError::FontNotFound {
+                context: self.error_context(ConrodBackendPrimitive::Text, 1),
+                family: family.to_owned(),
+            })
+        }
+    }
+
+    #[inline(always)]
+    fn skip(
+        &mut self,
+        primitive: ConrodBackendPrimitive,
+        points: usize,
+        feature: &'static str,
+    ) -> Result<(), DrawingErrorKind<ConrodBackendError>> {
+        // Report skipped operation (this is done in any case, even in strict mode)
+        self.graph.skipped.count(primitive);
+
+        if self.strict {
+            Err(DrawingErrorKind::DrawingError(
+                ConrodBackendError::UnsupportedFeature {
+                    context: self.error_context(primitive, points),
+                    feature,
+                },
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline(always)]
+    fn cull(
+        &mut self,
+        primitive: ConrodBackendPrimitive,
+        color: &BackendColor,
+        bounds: &[GeometryPoint; 2],
+    ) -> bool {
+        // Cull primitives that would not be visible, before any widget gets created for them \
+        //   (this saves a widget identifier and a Conrod widget update for each of them)
+        let culled = if color.alpha <= 0.0 {
+            self.graph.culled.transparent += 1;
+
+            true
+        } else if bounds[1][0] <= 0.0
+            || bounds[1][1] <= 0.0
+            || bounds[0][0] >= self.size.0 as ConrodScalar
+            || bounds[0][1] >= self.size.1 as ConrodScalar
+        {
+            self.graph.culled.off_canvas += 1;
+
+            true
+        } else {
+            false
+        };
+
+        if culled {
+            self.culled(primitive);
+        }
+
+        culled
+    }
+
+    #[inline(always)]
+    fn culled(&mut self, primitive: ConrodBackendPrimitive) {
+        self.graph.rendered.culled += 1;
+
+        // Notice: only primitives that get indexed in the hitmap consume an index within their \
+        //   series, as otherwise indices of following primitives would get shifted.
+        if let Some(hitmap) = self.hitmap {
+            if matches!(
+                primitive,
+                ConrodBackendPrimitive::Line
+                    | ConrodBackendPrimitive::Rect
+                    | ConrodBackendPrimitive::Path
+                    | ConrodBackendPrimitive::Circle
+            ) {
+                hitmap.skip_primitive();
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn degrade(&mut self) -> ConrodBackendDegradation {
+        // Acquire degradation stage from the time elapsed since the frame started (if a budget \
+        //   is set)
+        let degradation = match self.budget {
+            Some(budget) => {
+                // Notice: durations are compared as floating point seconds, as scaling the budget \
+                //   as a duration would overflow (and panic) for huge budgets (eg. 'Duration::MAX').
+                let (elapsed, budget) =
+                    (self.started.elapsed().as_secs_f64(), budget.as_secs_f64());
+
+                if elapsed > budget * DEGRADATION_OUTLINE_BUDGET_RATIO {
+                    ConrodBackendDegradation::Outline
+                } else if elapsed > budget * DEGRADATION_COARSE_BUDGET_RATIO {
+                    ConrodBackendDegradation::Coarse
+                } else if elapsed > budget {
+                    ConrodBackendDegradation::Decimate
+                } else {
+                    ConrodBackendDegradation::None
+                }
+            }
+            None => ConrodBackendDegradation::None,
+        };
+
+        self.graph.rendered.count_degradation(degradation);
+
+        degradation
+    }
+
+    #[inline(always)]
+    fn record<F: FnOnce() -> GeometryPrimitive>(&mut self, primitive: F) {
+        // Only build the primitive if recording is enabled, as building it might be expensive
+        if let Some(ref mut recording) = self.recording {
+            recording.primitives.push(primitive());
+        }
+    }
+
+    #[inline(always)]
+    fn with_scratch<F, R>(&mut self, scope: F) -> R
+    where
+        F: FnOnce(&mut Self, &mut GeometryScratch) -> R,
+    {
+        // Notice: the scratch buffers are moved out of the graph while in use, as drawing also \
+        //   needs to borrow the backend. Moving them around does not allocate, and they are \
+        //   restored afterwards so that their capacity gets re-used on the next frame.
+        let mut scratch = mem::take(&mut self.graph.scratch);
+
+        let result = scope(self, &mut scratch);
+
+        self.graph.scratch = scratch;
+
+        result
+    }
+
+    fn fill_polygon_points<S: BackendStyle>(
+        &mut self,
+        polygon_points: &[GeometryPoint],
+        style: &S,
+        scratch: &mut GeometryScratch,
+    ) -> Result<(), DrawingErrorKind<ConrodBackendError>> {
+        let polygon_thickness = style.stroke_width() as ConrodScalar;
+
+        if self.cull(
+            ConrodBackendPrimitive::Polygon,
+            &style.color(),
+            &geometry::bounds_of(
+                polygon_points,
+                if self.stroke_polygons {
+                    polygon_thickness / 2.0
+                } else {
+                    0.0
+                },
+            ),
+        ) {
+            return Ok(());
+        }
+
+        // Acquire absolute position generator (in parent container)
+        if let Some(position) = self.position() {
+            let polygon_color = color::Color::from(&style.color()).into();
+
+            // Only stroke the polygon outline if out of frame time budget (this skips \
+            //   triangulation, which is the most expensive operation)
+            if self.degrade() >= ConrodBackendDegradation::Outline {
+                if geometry::close_ring(polygon_points, &mut scratch.ring) {
+                    self.set_path(&position, &scratch.ring, polygon_color, polygon_thickness);
+
+                    self.record(|| GeometryPrimitive::Path {
+                        points: scratch.ring.clone(),
+                        color: polygon_color,
+                        thickness: polygon_thickness,
+                    });
+                }
+
+                self.graph.rendered.count(ConrodBackendPrimitive::Polygon);
+                self.graph.rendered.points_received += polygon_points.len();
+                self.graph.rendered.points_simplified += polygon_points.len();
+                self.graph.rendered.degraded += 1;
+
+                return Ok(());
+            }
+
+            // Retain triangles if recording is enabled
+            let mut recorded_triangles = self.recording.as_ref().map(|_| Vec::new());
+
+            // Notice: the time spent instantiating widgets while triangulating is deducted from \
+            //   the triangulation time, as it is already accounted for in the widgets time.
+            let (started, widgets_time_started) =
+                (Instant::now(), self.graph.rendered.widgets_time);
+
+            // Triangulate the polygon, and batch its triangles into a triangles widget
+            let triangulated = geometry::triangulate_polygon(
+                polygon_points.iter().copied(),
+                self.simplify.tolerance,
+                Some(self.parent),
+                scratch,
+                |triangle| {
+                    self.batch_triangle(&position, triangle, polygon_color);
+
+                    self.overlay_outline(triangle, true, false);
+
+                    if let Some(ref mut recorded_triangles) = recorded_triangles {
+                        recorded_triangles.push(*triangle);
+                    }
+                },
+            );
+
+            self.graph.rendered.triangulation_time += started
+                .elapsed()
+                .saturating_sub(self.graph.rendered.widgets_time - widgets_time_started);
+
+            triangulated.map_err(DrawingErrorKind::DrawingError)?;
+
+            self.graph.rendered.count(ConrodBackendPrimitive::Polygon);
+            self.graph.rendered.points_received += polygon_points.len();
+            self.graph.rendered.points_simplified += scratch.simplified.len();
+
+            if let Some(recorded_triangles) = recorded_triangles {
+                if !recorded_triangles.is_empty() {
+                    self.record(|| GeometryPrimitive::Fill {
+                        triangles: recorded_triangles,
+                        color: polygon_color,
+                    });
+                }
+            }
+
+            // Stroke outline on top of the fill (as a closed path)
+            if self.stroke_polygons && geometry::close_ring(polygon_points, &mut scratch.ring) {
+                self.set_path(&position, &scratch.ring, polygon_color, polygon_thickness);
+
+                self.record(|| GeometryPrimitive::Path {
+                    points: scratch.ring.clone(),
+                    color: polygon_color,
+                    thickness: polygon_thickness,
+                });
+            }
+
+            Ok(())
+        } else {
+            Err(DrawingErrorKind::DrawingError(
+                ConrodBackendError::NoParentPosition {
+                    context: self
+                        .error_context(ConrodBackendPrimitive::Polygon, polygon_points.len()),
+                },
+            ))
         }
     }
+
+    #[inline(always)]
+    fn set_line(
+        &mut self,
+        position: &position::PositionParent,
+        from: &GeometryPoint,
+        to: &GeometryPoint,
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+    ) {
+        // Render line as a quad, rather than using Conrod line widgets (this aligns lines on \
+        //   pixels as in the Bitmap backend, and prevents thin lines from disappearing)
+        if let Some(quad) = geometry::line_quad(from, to, thickness) {
+            self.batch_quad(position, &quad, color);
+        }
+    }
+
+    #[inline(always)]
+    fn set_rect(
+        &mut self,
+        position: &position::PositionParent,
+        upper_left: &GeometryPoint,
+        size: &[ConrodScalar; 2],
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+        fill: bool,
+    ) {
+        if fill {
+            self.batch_rect(position, upper_left, size, color);
+        } else {
+            // Render outline as a frame of filled rectangles (this matches the pixels covered by \
+            //   outlines in the Bitmap backend, including corners)
+            for (side_upper_left, side_size) in geometry::rect_outline(upper_left, size, thickness)
+                .iter()
+                .flatten()
+            {
+                self.batch_rect(position, side_upper_left, side_size, color);
+            }
+        }
+
+        self.overlay_box(upper_left, size, false);
+    }
+
+    #[inline(always)]
+    fn batch_rect(
+        &mut self,
+        position: &position::PositionParent,
+        upper_left: &GeometryPoint,
+        size: &[ConrodScalar; 2],
+        color: conrod::color::Color,
+    ) {
+        self.batch_quad(
+            position,
+            &[
+                *upper_left,
+                [upper_left[0] + size[0], upper_left[1]],
+                [upper_left[0] + size[0], upper_left[1] + size[1]],
+                [upper_left[0], upper_left[1] + size[1]],
+            ],
+            color,
+        );
+    }
+
+    #[inline(always)]
+    fn batch_quad(
+        &mut self,
+        position: &position::PositionParent,
+        quad: &[GeometryPoint; 4],
+        color: conrod::color::Color,
+    ) {
+        self.batch_triangle(position, &[quad[0], quad[1], quad[2]], color);
+        self.batch_triangle(position, &[quad[0], quad[2], quad[3]], color);
+    }
+
+    #[inline(always)]
+    fn batch_triangle(
+        &mut self,
+        position: &position::PositionParent,
+        triangle: &[GeometryPoint; 3],
+        color: conrod::color::Color,
+    ) {
+        // Consecutive triangles of the same color are batched into a single triangles widget \
+        //   (eg. histogram bars, mesh lines or polygon fills), as creating one widget per quad \
+        //   or per triangle is expensive
+        if self
+            .graph
+            .batch
+            .color
+            .map_or(false, |batch_color| batch_color != color)
+        {
+            self.flush_batch();
+        }
+
+        self.graph.batch.color = Some(color);
+        self.graph.rendered.triangles += 1;
+
+        self.graph
+            .batch
+            .triangles
+            .push(conrod::widget::triangles::Triangle([
+                position.abs_point_geometry(&triangle[0]),
+                position.abs_point_geometry(&triangle[1]),
+                position.abs_point_geometry(&triangle[2]),
+            ]));
+    }
+
+    fn flush_batch(&mut self) {
+        if let Some(color) = self.graph.batch.color.take() {
+            if !self.graph.batch.triangles.is_empty() {
+                let (bounds, started) = (self.graph.batch.bounds(), Instant::now());
+
+                // Render triangles widget (from all batched quads)
+                conrod::widget::triangles::Triangles::single_color(
+                    color.to_rgb(),
+                    self.graph.batch.triangles.drain(..),
+                )
+                .with_bounding_rect(bounds)
+                .parent(self.parent)
+                .graphics_for(self.parent)
+                .set(self.graph.mesh.next(self.ui), self.ui);
+
+                self.graph.rendered.count_widget(started);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn overlay_outline(&mut self, points: &[GeometryPoint], closed: bool, transformed: bool) {
+        // Only render overlay if debug mode is enabled
+        if !self.debug {
+            return;
+        }
+
+        if let Some(position) = self.position() {
+            let edges = if closed {
+                points.len()
+            } else {
+                points.len().saturating_sub(1)
+            };
+
+            for index in 0..edges {
+                if let Some(quad) = geometry::line_quad(
+                    &points[index],
+                    &points[(index + 1) % points.len()],
+                    DEBUG_OVERLAY_THICKNESS,
+                ) {
+                    // Notice: points of widgets that Conrod positions on their own (ie. circles \
+                    //   and texts) are already transformed, as their boxes get placed after the \
+                    //   global transform.
+                    let abs_point = |point: &GeometryPoint| {
+                        if transformed {
+                            position.abs_point_transformed(point)
+                        } else {
+                            position.abs_point_geometry(point)
+                        }
+                    };
+
+                    let (first, second) = conrod::widget::triangles::from_quad([
+                        abs_point(&quad[0]),
+                        abs_point(&quad[1]),
+                        abs_point(&quad[2]),
+                        abs_point(&quad[3]),
+                    ]);
+
+                    self.graph.overlay_batch.triangles.push(first);
+                    self.graph.overlay_batch.triangles.push(second);
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn overlay_box(
+        &mut self,
+        upper_left: &GeometryPoint,
+        size: &[ConrodScalar; 2],
+        transformed: bool,
+    ) {
+        self.overlay_outline(
+            &[
+                *upper_left,
+                [upper_left[0] + size[0], upper_left[1]],
+                [upper_left[0] + size[0], upper_left[1] + size[1]],
+                [upper_left[0], upper_left[1] + size[1]],
+            ],
+            true,
+            transformed,
+        );
+    }
+
+    #[inline(always)]
+    fn overlay_anchor(&mut self, point: &GeometryPoint, transformed: bool) {
+        self.overlay_outline(
+            &[
+                [point[0] - DEBUG_OVERLAY_ANCHOR_SIZE, point[1]],
+                [point[0] + DEBUG_OVERLAY_ANCHOR_SIZE, point[1]],
+            ],
+            false,
+            transformed,
+        );
+        self.overlay_outline(
+            &[
+                [point[0], point[1] - DEBUG_OVERLAY_ANCHOR_SIZE],
+                [point[0], point[1] + DEBUG_OVERLAY_ANCHOR_SIZE],
+            ],
+            false,
+            transformed,
+        );
+    }
+
+    fn flush_overlay(&mut self) {
+        if !self.debug {
+            return;
+        }
+
+        // Render overlay triangles widget (on top of everything else)
+        if !self.graph.overlay_batch.triangles.is_empty() {
+            let (bounds, started) = (self.graph.overlay_batch.bounds(), Instant::now());
+
+            conrod::widget::triangles::Triangles::single_color(
+                DEBUG_OVERLAY_COLOR.to_rgb(),
+                self.graph.overlay_batch.triangles.drain(..),
+            )
+            .with_bounding_rect(bounds)
+            .parent(self.parent)
+            .graphics_for(self.parent)
+            .set(self.graph.overlay_mesh.next(self.ui), self.ui);
+
+            self.graph.rendered.count_widget(started);
+        }
+
+        // Render counters label in the corner of the parent (if the font is available)
+        if self.ui.fonts.get(self.font).is_some() {
+            let rendered = &self.graph.rendered;
+
+            let label = format!(
+                "{} primitives, {} widgets, {} triangles, {} culled, {} skipped",
+                rendered.primitives(),
+                rendered.widgets,
+                rendered.triangles,
+                rendered.culled,
+                self.graph.skipped.total(),
+            );
+
+            let started = Instant::now();
+
+            conrod::widget::Text::new(&label)
+                .font_id(self.font)
+                .font_size(DEBUG_OVERLAY_FONT_SIZE)
+                .color(DEBUG_OVERLAY_COLOR)
+                .top_left_with_margins_on(self.parent, DEBUG_OVERLAY_MARGIN, DEBUG_OVERLAY_MARGIN)
+                .graphics_for(self.parent)
+                .set(self.graph.overlay_text.next(self.ui), self.ui);
+
+            self.graph.rendered.count_widget(started);
+        }
+    }
+
+    #[inline(always)]
+    fn set_path(
+        &mut self,
+        position: &position::PositionParent,
+        points: &[GeometryPoint],
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+    ) {
+        // Render closed paths as a triangulated ring, as point path widgets do not join their \
+        //   last segment back to their first one
+        if geometry::path_is_closed(points) {
+            geometry::stroke_ring(points, thickness, |triangle| {
+                self.batch_triangle(position, triangle, color)
+            });
+
+            return;
+        }
+
+        // Generate line style
+        let line_style = conrod::widget::primitive::line::Style::solid()
+            .color(color)
+            .thickness(thickness);
+
+        self.flush_batch();
+
+        let started = Instant::now();
+
+        // Render point path widget
+        conrod::widget::point_path::PointPath::abs_styled(
+            geometry::stroke_points(points, thickness)
+                .map(|point| position.abs_point_geometry(&point)),
+            line_style,
+        )
+        .top_left_of(self.parent)
+        .graphics_for(self.parent)
+        .set(self.graph.path.next(self.ui), self.ui);
+
+        self.graph.rendered.count_widget(started);
+    }
+
+    #[inline(always)]
+    fn set_circle(
+        &mut self,
+        position: &position::PositionParent,
+        center: &GeometryPoint,
+        radius: ConrodScalar,
+        style: conrod::widget::primitive::shape::Style,
+        resolution: Option<usize>,
+    ) {
+        self.flush_batch();
+
+        let started = Instant::now();
+
+        // Apply the global transform to the circle (which turns it into an axis-aligned oval, \
+        //   as its radius may get scaled differently on each axis)
+        let (center, [radius_x, radius_y]) = (
+            position.transform_point(center),
+            position.transform_extents(radius),
+        );
+
+        let oval = conrod::widget::Oval::styled([radius_x * 2.0, radius_y * 2.0], style);
+
+        // Render circle widget (with fewer segments if coarse)
+        if let Some(resolution) = resolution {
+            oval.resolution(resolution)
+        } else {
+            oval
+        }
+        .top_left_with_margins_on(self.parent, center[1] - radius_y, center[0] - radius_x)
+        .graphics_for(self.parent)
+        .set(self.graph.circle.next(self.ui), self.ui);
+
+        self.graph.rendered.count_widget(started);
+
+        self.overlay_box(
+            &[center[0] - radius_x, center[1] - radius_y],
+            &[radius_x * 2.0, radius_y * 2.0],
+            true,
+        );
+        self.overlay_anchor(&center, true);
+    }
+
+    #[inline(always)]
+    fn set_text(
+        &mut self,
+        position: &position::PositionParent,
+        text: &str,
+        pos: &GeometryPoint,
+        size: ConrodScalar,
+        color: conrod::color::Color,
+        justify: conrod::text::Justify,
+    ) {
+        // Adapt font style from rasterizer style to Conrod
+        let (text_width_estimated, font_size_final) = convert::font_style(text, size);
+
+        // Generate text style
+        let text_style = conrod::widget::primitive::text::Style {
+            color: Some(color),
+            font_id: Some(Some(self.font)),
+            font_size: Some(font_size_final),
+            justify: Some(justify),
+
+            ..Default::default()
+        };
+
+        // Move the text anchor with the global transform (the text itself does not get rotated, \
+        //   as Conrod does not support text transforms)
+        let pos = position.transform_point(pos);
+
+        self.flush_batch();
+
+        let started = Instant::now();
+
+        // Render text widget
+        conrod::widget::Text::new(text)
+            .with_style(text_style)
+            .top_left_with_margins_on(
+                self.parent,
+                pos[1] - (size / 2.0 + 1.0),
+                pos[0] - text_width_estimated,
+            )
+            .graphics_for(self.parent)
+            .set(self.graph.text.next(self.ui), self.ui);
+
+        self.graph.rendered.count_widget(started);
+
+        // Notice: the text box is estimated, as Conrod sizes text widgets from their glyphs
+        self.overlay_box(
+            &[pos[0] - text_width_estimated, pos[1] - (size / 2.0 + 1.0)],
+            &[text_width_estimated, font_size_final as ConrodScalar],
+            true,
+        );
+        self.overlay_anchor(&pos, true);
+    }
+
+    #[inline(always)]
+    fn shape_style(
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+        fill: bool,
+    ) -> conrod::widget::primitive::shape::Style {
+        if fill {
+            conrod::widget::primitive::shape::Style::fill_with(color)
+        } else {
+            conrod::widget::primitive::shape::Style::outline_styled(
+                conrod::widget::primitive::line::Style::new()
+                    .color(color)
+                    .thickness(thickness),
+            )
+        }
+    }
+}
+
+impl<'a, 'b> Drop for ConrodBackend<'a, 'b> {
+    fn drop(&mut self) {
+        // Render pending batched quads, as 'present()' might not have been called
+        self.flush_batch();
+
+        // Render debug overlay last, so that it shows on top of the plot
+        self.flush_overlay();
+    }
 }
 
 impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
@@ -67,14 +988,26 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
     }
 
     fn present(&mut self) -> Result<(), DrawingErrorKind<ConrodBackendError>> {
+        self.flush_batch();
+
         Ok(())
     }
 
     fn draw_pixel(
         &mut self,
-        _point: BackendCoord,
-        _color: BackendColor,
+        point: BackendCoord,
+        color: BackendColor,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let pixel_point = geometry::point_from_coord(&point);
+
+        if self.cull(
+            ConrodBackendPrimitive::Pixel,
+            &color,
+            &[pixel_point, [pixel_point[0] + 1.0, pixel_point[1] + 1.0]],
+        ) {
+            return Ok(());
+        }
+
         // Not supported yet (rendering ignored)
         // Notice: doing this efficiently would require building an internal buffer on 'self', and \
         //   rendering it as a Conrod image widget when the final call to 'present()' is done. \
@@ -82,7 +1015,7 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         //   super inefficient. Note that this buffer would be shared with 'blit_bitmap()', and \
         //   thus alpha-channel pixels would need to be blended accordingly.
 
-        Ok(())
+        self.skip(ConrodBackendPrimitive::Pixel, 1, "pixel rendering")
     }
 
     fn draw_line<S: BackendStyle>(
@@ -91,26 +1024,60 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         to: BackendCoord,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let line_thickness = style.stroke_width() as ConrodScalar;
+
+        if self.cull(
+            ConrodBackendPrimitive::Line,
+            &style.color(),
+            &geometry::bounds_of(
+                &[
+                    geometry::point_from_coord(&from),
+                    geometry::point_from_coord(&to),
+                ],
+                line_thickness / 2.0,
+            ),
+        ) {
+            return Ok(());
+        }
+
         // Acquire absolute position generator (in parent container)
-        if let Some(position) = position::PositionParent::from(&self.ui, self.parent) {
-            // Generate line style
-            let line_style = conrod::widget::primitive::line::Style::solid()
-                .color(color::Color::from(&style.color()).into())
-                .thickness(style.stroke_width() as ConrodScalar);
+        if let Some(position) = self.position() {
+            let line_color = color::Color::from(&style.color()).into();
 
             // Render line widget
-            conrod::widget::line::Line::abs_styled(
-                position.abs_point_conrod_scalar(&from),
-                position.abs_point_conrod_scalar(&to),
-                line_style,
-            )
-            .top_left_of(self.parent)
-            .set(self.graph.line.next(&mut self.ui), &mut self.ui);
+            self.set_line(
+                &position,
+                &geometry::point_from_coord(&from),
+                &geometry::point_from_coord(&to),
+                line_color,
+                line_thickness,
+            );
+
+            self.graph.rendered.count(ConrodBackendPrimitive::Line);
+
+            if let Some(hitmap) = self.hitmap {
+                hitmap.insert_path(
+                    &[
+                        geometry::point_from_coord(&from),
+                        geometry::point_from_coord(&to),
+                    ],
+                    line_thickness,
+                );
+            }
+
+            self.record(|| GeometryPrimitive::Line {
+                from: geometry::point_from_coord(&from),
+                to: geometry::point_from_coord(&to),
+                color: line_color,
+                thickness: line_thickness,
+            });
 
             Ok(())
         } else {
             Err(DrawingErrorKind::DrawingError(
-                ConrodBackendError::NoParentPosition,
+                ConrodBackendError::NoParentPosition {
+                    context: self.error_context(ConrodBackendPrimitive::Line, 2),
+                },
             ))
         }
     }
@@ -122,67 +1089,145 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         style: &S,
         fill: bool,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        // Generate rectangle style
-        let rectangle_style = if fill {
-            conrod::widget::primitive::shape::Style::fill_with(
-                color::Color::from(&style.color()).into(),
-            )
-        } else {
-            conrod::widget::primitive::shape::Style::outline_styled(
-                conrod::widget::primitive::line::Style::new()
-                    .color(color::Color::from(&style.color()).into())
-                    .thickness(style.stroke_width() as ConrodScalar),
-            )
-        };
+        let ((rect_upper_left, rect_size), rect_color, rect_thickness) = (
+            geometry::rect_from_corners(&upper_left, &bottom_right),
+            color::Color::from(&style.color()).into(),
+            style.stroke_width() as ConrodScalar,
+        );
 
-        // Render rectangle widget
-        conrod::widget::rectangle::Rectangle::styled(
-            [
-                (bottom_right.0 - upper_left.0) as ConrodScalar,
-                (bottom_right.1 - upper_left.1) as ConrodScalar,
-            ],
-            rectangle_style,
-        )
-        .top_left_with_margins_on(
-            self.parent,
-            upper_left.1 as ConrodScalar,
-            upper_left.0 as ConrodScalar,
-        )
-        .set(self.graph.rect.next(&mut self.ui), &mut self.ui);
+        // Cull rectangles that would not cover any pixel
+        if geometry::rect_is_empty(&rect_size, fill) {
+            self.graph.culled.empty += 1;
+            self.culled(ConrodBackendPrimitive::Rect);
 
-        Ok(())
-    }
+            return Ok(());
+        }
+
+        if self.cull(
+            ConrodBackendPrimitive::Rect,
+            &style.color(),
+            &geometry::bounds_of(
+                &[
+                    rect_upper_left,
+                    [
+                        rect_upper_left[0] + rect_size[0],
+                        rect_upper_left[1] + rect_size[1],
+                    ],
+                ],
+                if fill { 0.0 } else { rect_thickness + 0.5 },
+            ),
+        ) {
+            return Ok(());
+        }
 
-    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
-        &mut self,
-        path: I,
-        style: &S,
-    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
         // Acquire absolute position generator (in parent container)
-        if let Some(position) = position::PositionParent::from(&self.ui, self.parent) {
-            // Generate line style
-            let line_style = conrod::widget::primitive::line::Style::solid()
-                .color(color::Color::from(&style.color()).into())
-                .thickness(style.stroke_width() as ConrodScalar);
-
-            // Render point path widget
-            conrod::widget::point_path::PointPath::abs_styled(
-                path.into_iter()
-                    .map(|point| position.abs_point_conrod_scalar(&point))
-                    .collect::<Vec<conrod::position::Point>>(),
-                line_style,
-            )
-            .top_left_of(self.parent)
-            .set(self.graph.path.next(&mut self.ui), &mut self.ui);
+        if let Some(position) = self.position() {
+            // Render rectangle (batched with consecutive rectangles of the same color)
+            self.set_rect(
+                &position,
+                &rect_upper_left,
+                &rect_size,
+                rect_color,
+                rect_thickness,
+                fill,
+            );
+
+            self.graph.rendered.count(ConrodBackendPrimitive::Rect);
+
+            if let Some(hitmap) = self.hitmap {
+                hitmap.insert_rect(
+                    &rect_upper_left,
+                    &[
+                        rect_upper_left[0] + rect_size[0],
+                        rect_upper_left[1] + rect_size[1],
+                    ],
+                );
+            }
+
+            self.record(|| GeometryPrimitive::Rect {
+                upper_left: rect_upper_left,
+                size: rect_size,
+                color: rect_color,
+                thickness: rect_thickness,
+                fill,
+            });
 
             Ok(())
         } else {
             Err(DrawingErrorKind::DrawingError(
-                ConrodBackendError::NoParentPosition,
+                ConrodBackendError::NoParentPosition {
+                    context: self.error_context(ConrodBackendPrimitive::Rect, 2),
+                },
             ))
         }
     }
 
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.with_scratch(|backend, scratch| {
+            let (path_points, path_thickness) =
+                (&mut scratch.points, style.stroke_width() as ConrodScalar);
+
+            let mut points_received = 0;
+
+            // Degrade path precision if out of frame time budget
+            let degradation = backend.degrade();
+
+            geometry::simplify_path(
+                path.into_iter().inspect(|_| points_received += 1),
+                &backend.simplify.degrade(degradation),
+                path_points,
+            );
+
+            backend.graph.rendered.points_received += points_received;
+            backend.graph.rendered.points_simplified += path_points.len();
+
+            if backend.cull(
+                ConrodBackendPrimitive::Path,
+                &style.color(),
+                &geometry::bounds_of(path_points.iter(), path_thickness / 2.0),
+            ) {
+                return Ok(());
+            }
+
+            // Acquire absolute position generator (in parent container)
+            if let Some(position) = backend.position() {
+                let path_color = color::Color::from(&style.color()).into();
+
+                // Render point path widget
+                backend.set_path(&position, path_points, path_color, path_thickness);
+
+                backend.graph.rendered.count(ConrodBackendPrimitive::Path);
+
+                if degradation > ConrodBackendDegradation::None {
+                    backend.graph.rendered.degraded += 1;
+                }
+
+                if let Some(hitmap) = backend.hitmap {
+                    hitmap.insert_path(path_points.iter(), path_thickness);
+                }
+
+                backend.record(|| GeometryPrimitive::Path {
+                    points: path_points.clone(),
+                    color: path_color,
+                    thickness: path_thickness,
+                });
+
+                Ok(())
+            } else {
+                Err(DrawingErrorKind::DrawingError(
+                    ConrodBackendError::NoParentPosition {
+                        context: backend
+                            .error_context(ConrodBackendPrimitive::Path, path_points.len()),
+                    },
+                ))
+            }
+        })
+    }
+
     fn draw_circle<S: BackendStyle>(
         &mut self,
         center: BackendCoord,
@@ -190,27 +1235,60 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         style: &S,
         fill: bool,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        // Generate circle style
-        let circle_style = if fill {
-            conrod::widget::primitive::shape::Style::fill_with(
-                color::Color::from(&style.color()).into(),
-            )
+        let (circle_center, circle_radius, circle_color, circle_thickness) = (
+            geometry::point_from_coord(&center),
+            radius as ConrodScalar,
+            color::Color::from(&style.color()).into(),
+            style.stroke_width() as ConrodScalar,
+        );
+
+        if self.cull(
+            ConrodBackendPrimitive::Circle,
+            &style.color(),
+            &geometry::bounds_of(&[circle_center], circle_radius + circle_thickness),
+        ) {
+            return Ok(());
+        }
+
+        let position = self.position().ok_or_else(|| {
+            DrawingErrorKind::DrawingError(ConrodBackendError::NoParentPosition {
+                context: self.error_context(ConrodBackendPrimitive::Circle, 1),
+            })
+        })?;
+
+        // Render circle widget (with fewer segments if out of frame time budget)
+        let circle_resolution = if self.degrade() >= ConrodBackendDegradation::Coarse {
+            Some(CIRCLE_COARSE_RESOLUTION)
         } else {
-            conrod::widget::primitive::shape::Style::outline_styled(
-                conrod::widget::primitive::line::Style::new()
-                    .color(color::Color::from(&style.color()).into())
-                    .thickness(style.stroke_width() as ConrodScalar),
-            )
+            None
         };
 
-        // Render circle widget
-        conrod::widget::circle::Circle::styled(radius as ConrodScalar, circle_style)
-            .top_left_with_margins_on(
-                self.parent,
-                (center.1 - radius as i32) as ConrodScalar,
-                (center.0 - radius as i32) as ConrodScalar,
-            )
-            .set(self.graph.circle.next(&mut self.ui), &mut self.ui);
+        self.set_circle(
+            &position,
+            &circle_center,
+            circle_radius,
+            Self::shape_style(circle_color, circle_thickness, fill),
+            circle_resolution,
+        );
+
+        self.graph.rendered.count(ConrodBackendPrimitive::Circle);
+
+        if circle_resolution.is_some() {
+            self.graph.rendered.degraded += 1;
+        }
+
+        if let Some(hitmap) = self.hitmap {
+            hitmap.insert_circle(&circle_center, circle_radius);
+        }
+
+        self.record(|| GeometryPrimitive::Circle {
+            center: circle_center,
+            radius: circle_radius,
+            color: circle_color,
+            thickness: circle_thickness,
+            fill,
+            resolution: circle_resolution,
+        });
 
         Ok(())
     }
@@ -220,52 +1298,23 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         vert: I,
         style: &S,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        // Acquire absolute position generator (in parent container)
-        if let Some(position) = position::PositionParent::from(&self.ui, self.parent) {
-            // Paint a simplified path, where empty areas are removed and un-necessary points are \
-            //   cleared. This is required for triangulation to work properly, and it reduces \
-            //   the number of triangles on screen to a strict minimum.
-            let simplified_path: Vec<_> = path::PathSimplifier::from(
+        self.with_scratch(|backend, scratch| {
+            // Notice: the points buffer is taken from the scratch while triangulating, as the \
+            //   triangulation borrows the rest of the scratch.
+            let mut polygon_points = mem::take(&mut scratch.points);
+
+            polygon_points.clear();
+            polygon_points.extend(
                 vert.into_iter()
-                    .map(|vertex| position.abs_point_path_simplifier(&vertex)),
-            )
-            .collect();
-
-            // Find closed shapes (eg. when the plot area goes from positive to negative, we need \
-            //   to split the path into two distinct paths, otherwise we will not be able to \
-            //   triangulate properly, and thus we will not be able to fill the shape)
-            if let Ok(mut shape_splitter) = shape::ShapeSplitter::try_from(&simplified_path) {
-                // Generate polygon style
-                let polygon_style = conrod::widget::primitive::shape::Style::fill_with(
-                    color::Color::from(&style.color()).into(),
-                );
+                    .map(|point| geometry::point_from_coord(&point)),
+            );
 
-                // Triangulate the polygon points, giving back a list of triangles that can be \
-                //   filled into a contiguous area.
-                // Notice: this method takes into account concave shapes
-                for shape_points in shape_splitter.collect() {
-                    // Is that enough points to form at least a triangle?
-                    if shape_points.len() >= 3 {
-                        let triangles = triangulate::triangulate_points(shape_points.iter());
-
-                        for index in 0..triangles.size() {
-                            conrod::widget::polygon::Polygon::abs_styled(
-                                triangles.get_triangle(index).points.iter().copied(),
-                                polygon_style,
-                            )
-                            .top_left_of(self.parent)
-                            .set(self.graph.fill.next(&mut self.ui), &mut self.ui);
-                        }
-                    }
-                }
-            }
+            let filled = backend.fill_polygon_points(&polygon_points, style, scratch);
 
-            Ok(())
-        } else {
-            Err(DrawingErrorKind::DrawingError(
-                ConrodBackendError::NoParentPosition,
-            ))
-        }
+            scratch.points = polygon_points;
+
+            filled
+        })
     }
 
     fn draw_text<S: BackendTextStyle>(
@@ -274,31 +1323,61 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         style: &S,
         pos: BackendCoord,
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
-        // Adapt font style from rasterizer style to Conrod
-        let (text_width_estimated, font_size_final) = convert::font_style(text, style.size());
+        let (text_pos, text_size, text_color, text_justify) = (
+            geometry::point_from_coord(&pos),
+            geometry::text_size(style.size(), Some(self.parent))
+                .map_err(DrawingErrorKind::DrawingError)?,
+            color::Color::from(&style.color()).into(),
+            geometry::justify_from_anchor(style.anchor().h_pos),
+        );
 
-        // Generate text style
-        let mut text_style = conrod::widget::primitive::text::Style::default();
+        // Notice: as the text box is estimated, and its anchor is not accounted for, bounds are \
+        //   enlarged as to cover the text box whatever its anchor.
+        let text_width_estimated = convert::font_style(text, text_size).0;
 
-        text_style.color = Some(color::Color::from(&style.color()).into());
-        text_style.font_id = Some(Some(self.font));
-        text_style.font_size = Some(font_size_final);
+        if self.cull(
+            ConrodBackendPrimitive::Text,
+            &style.color(),
+            &geometry::bounds_of(&[text_pos], text_width_estimated.max(text_size)),
+        ) {
+            return Ok(());
+        }
 
-        text_style.justify = Some(match style.anchor().h_pos {
-            text_anchor::HPos::Left => conrod::text::Justify::Left,
-            text_anchor::HPos::Right => conrod::text::Justify::Right,
-            text_anchor::HPos::Center => conrod::text::Justify::Center,
-        });
+        self.check_font(style.family().as_str())
+            .map_err(DrawingErrorKind::DrawingError)?;
+
+        // Text transforms are not supported by Conrod (text gets rendered without its transform)
+        if !matches!(style.transform(), FontTransform::None) {
+            self.skip(ConrodBackendPrimitive::Text, 1, "text transform")?;
+        }
+
+        let position = self.position().ok_or_else(|| {
+            DrawingErrorKind::DrawingError(ConrodBackendError::NoParentPosition {
+                context: self.error_context(ConrodBackendPrimitive::Text, 1),
+            })
+        })?;
 
         // Render text widget
-        conrod::widget::Text::new(text)
-            .with_style(text_style)
-            .top_left_with_margins_on(
-                self.parent,
-                pos.1 as ConrodScalar - (style.size() / 2.0 + 1.0),
-                pos.0 as ConrodScalar - text_width_estimated,
-            )
-            .set(self.graph.text.next(&mut self.ui), &mut self.ui);
+        self.set_text(
+            &position,
+            text,
+            &text_pos,
+            text_size,
+            text_color,
+            text_justify,
+        );
+
+        self.graph.rendered.count(ConrodBackendPrimitive::Text);
+
+        self.record(|| GeometryPrimitive::Text {
+            text: text.to_owned(),
+            pos: text_pos,
+            size: text_size,
+            color: text_color,
+            justify: text_justify,
+            family: style.family().as_str().to_owned(),
+            style: style.style(),
+        });
 
         Ok(())
     }
@@ -317,7 +1396,7 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
     fn blit_bitmap(
         &mut self,
         _pos: BackendCoord,
-        (_iw, _ih): (u32, u32),
+        size: (u32, u32),
         _src: &[u8],
     ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
         // Not supported yet (rendering ignored)
@@ -326,6 +1405,9 @@ impl<'a, 'b> DrawingBackend for ConrodBackend<'a, 'b> {
         //   Note that this buffer would be shared with 'draw_pixel()', and thus alpha-channel \
         //   pixels would need to be blended accordingly.
 
-        Ok(())
+        let points = geometry::bitmap_points(size, Some(self.parent))
+            .map_err(DrawingErrorKind::DrawingError)?;
+
+        self.skip(ConrodBackendPrimitive::Bitmap, points, "bitmap rendering")
     }
 }
@@ -4,16 +4,172 @@
 // Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
 // License: MIT
 
+use conrod_core as conrod;
+
 #[derive(Debug)]
 /// Indicates that some error occured within the Conrod backend
 pub enum ConrodBackendError {
     /// The parent widget position could not be acquired, is the parent widget drawn in Conrod?
-    NoParentPosition,
+    NoParentPosition {
+        /// The context of the primitive that failed to draw
+        context: ConrodBackendErrorContext,
+    },
+    /// A polygon could not be triangulated (eg. degenerate geometry)
+    TriangulationFailed {
+        /// The context of the primitive that failed to draw
+        context: ConrodBackendErrorContext,
+        /// The reason reported by the triangulation library
+        reason: String,
+    },
+    /// The geometry of a primitive is invalid (eg. not-a-number values)
+    InvalidGeometry {
+        /// The context of the primitive that failed to draw
+        context: ConrodBackendErrorContext,
+        /// The reason why the geometry is invalid
+        reason: &'static str,
+    },
+    /// A feature that is not supported by the Conrod backend was requested (eg. pixels, bitmaps or text transforms)
+    UnsupportedFeature {
+        /// The context of the primitive that failed to draw
+        context: ConrodBackendErrorContext,
+        /// The feature that is not supported
+        feature: &'static str,
+    },
+    /// The font used to draw text could not be found in Conrod, was it inserted in the Conrod fonts map?
+    FontNotFound {
+        /// The context of the primitive that failed to draw
+        context: ConrodBackendErrorContext,
+        /// The font family requested by Plotters
+        family: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The context in which a Conrod backend error occured
+pub struct ConrodBackendErrorContext {
+    /// The kind of primitive that failed to draw
+    pub primitive: ConrodBackendPrimitive,
+    /// The parent widget of the plot (if any, as geometry builders are not bound to a parent widget)
+    pub parent: Option<conrod::widget::Id>,
+    /// The number of points in the primitive that failed to draw
+    pub points: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kind of a primitive drawn by the Conrod backend
+pub enum ConrodBackendPrimitive {
+    /// A pixel (from `draw_pixel()`)
+    Pixel,
+    /// A line (from `draw_line()`)
+    Line,
+    /// A rectangle (from `draw_rect()`)
+    Rect,
+    /// A path (from `draw_path()`)
+    Path,
+    /// A circle (from `draw_circle()`)
+    Circle,
+    /// A filled polygon (from `fill_polygon()`)
+    Polygon,
+    /// A text (from `draw_text()`)
+    Text,
+    /// A bitmap (from `blit_bitmap()`)
+    Bitmap,
+}
+
+impl ConrodBackendError {
+    /// Get the context in which the error occured
+    pub fn context(&self) -> &ConrodBackendErrorContext {
+        match self {
+            ConrodBackendError::NoParentPosition { context }
+            | ConrodBackendError::TriangulationFailed { context, .. }
+            | ConrodBackendError::InvalidGeometry { context, .. }
+            | ConrodBackendError::UnsupportedFeature { context, .. }
+            | ConrodBackendError::FontNotFound { context, .. } => context,
+        }
+    }
+
+    /// Check whether the error only affects the primitive that failed to draw
+    ///
+    /// Recoverable errors can be safely ignored, as the rest of the plot can still be drawn (only \
+    ///   the failed primitive will be missing). Non-recoverable errors will affect all subsequent \
+    ///   primitives.
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(self, ConrodBackendError::NoParentPosition { .. })
+    }
+}
+
+impl ConrodBackendErrorContext {
+    pub(crate) fn new(
+        primitive: ConrodBackendPrimitive,
+        parent: Option<conrod::widget::Id>,
+        points: usize,
+    ) -> Self {
+        Self {
+            primitive,
+            parent,
+            points,
+        }
+    }
 }
 
 impl std::fmt::Display for ConrodBackendError {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(fmt, "{:?}", self)
+        match self {
+            ConrodBackendError::NoParentPosition { context } => write!(
+                fmt,
+                "could not draw {}: parent widget position is unknown (is it drawn?)",
+                context
+            ),
+            ConrodBackendError::TriangulationFailed { context, reason } => {
+                write!(fmt, "could not triangulate {}: {}", context, reason)
+            }
+            ConrodBackendError::InvalidGeometry { context, reason } => {
+                write!(
+                    fmt,
+                    "could not draw {}: invalid geometry, {}",
+                    context, reason
+                )
+            }
+            ConrodBackendError::UnsupportedFeature { context, feature } => {
+                write!(
+                    fmt,
+                    "could not draw {}: {} is not supported",
+                    context, feature
+                )
+            }
+            ConrodBackendError::FontNotFound { context, family } => write!(
+                fmt,
+                "could not draw {}: font for family '{}' not found in Conrod",
+                context, family
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for ConrodBackendErrorContext {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{} with {} point(s)", self.primitive, self.points)?;
+
+        if let Some(parent) = self.parent {
+            write!(fmt, " in parent widget {:?}", parent)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ConrodBackendPrimitive {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(match self {
+            ConrodBackendPrimitive::Pixel => "pixel",
+            ConrodBackendPrimitive::Line => "line",
+            ConrodBackendPrimitive::Rect => "rectangle",
+            ConrodBackendPrimitive::Path => "path",
+            ConrodBackendPrimitive::Circle => "circle",
+            ConrodBackendPrimitive::Polygon => "polygon",
+            ConrodBackendPrimitive::Text => "text",
+            ConrodBackendPrimitive::Bitmap => "bitmap",
+        })
     }
 }
 
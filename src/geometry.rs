@@ -0,0 +1,1193 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use std::mem;
+
+use conrod_core::{self as conrod, position::Scalar as ConrodScalar};
+use plotters_backend::{
+    text_anchor, BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend,
+    DrawingErrorKind, FontStyle, FontTransform,
+};
+
+use crate::error::{ConrodBackendError, ConrodBackendErrorContext, ConrodBackendPrimitive};
+use crate::report::{ConrodBackendDegradation, ConrodBackendSkipReport};
+use crate::triangulate;
+use crate::utils::{color, convert, monotone, path, shape};
+
+pub(crate) type GeometryPoint = [ConrodScalar; 2];
+
+const LINE_WIDTH_MINIMUM: ConrodScalar = 1.0;
+const STROKE_MITER_LIMIT: ConrodScalar = 4.0;
+const PATH_COARSE_TOLERANCE: ConrodScalar = 1.0;
+
+/// The prepared geometry of a plot, that can be built on any thread and then submitted to Conrod
+///
+/// All coordinates are stored relative to the top-left corner of the parent widget, in backend \
+///   pixels. Expensive operations (ie. path simplification, shape splitting and triangulation) \
+///   are already done, so that submitting this geometry to Conrod only instantiates widgets.
+#[derive(Clone, Default)]
+pub struct ConrodBackendGeometry {
+    pub(crate) size: (u32, u32),
+    pub(crate) primitives: Vec<GeometryPrimitive>,
+    pub(crate) skipped: ConrodBackendSkipReport,
+}
+
+/// The CPU-only Plotters backend, used to build a `ConrodBackendGeometry` outside of the UI thread
+pub struct ConrodBackendGeometryBuilder<'a> {
+    geometry: &'a mut ConrodBackendGeometry,
+    simplify: GeometrySimplify,
+    stroke_polygons: bool,
+    strict: bool,
+    scratch: GeometryScratch,
+}
+
+#[derive(Clone, Copy, Default)]
+pub(crate) struct GeometrySimplify {
+    pub(crate) tolerance: ConrodScalar,
+    pub(crate) paths: bool,
+    pub(crate) decimate: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct GeometryScratch {
+    pub(crate) points: Vec<GeometryPoint>,
+    pub(crate) ring: Vec<GeometryPoint>,
+    pub(crate) simplified: Vec<GeometryPoint>,
+    pub(crate) splitter: shape::ShapeSplitter,
+    pub(crate) nester: shape::ShapeNester,
+    pub(crate) triangulator: triangulate::Triangulator,
+}
+
+impl GeometrySimplify {
+    #[inline(always)]
+    pub(crate) fn degrade(mut self, degradation: ConrodBackendDegradation) -> Self {
+        // Trade path precision for speed, as the frame time budget was exceeded
+        if degradation >= ConrodBackendDegradation::Decimate {
+            self.decimate = true;
+        }
+
+        if degradation >= ConrodBackendDegradation::Coarse {
+            self.tolerance = self.tolerance.max(PATH_COARSE_TOLERANCE);
+            self.paths = true;
+        }
+
+        self
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum GeometryPrimitive {
+    Line {
+        from: GeometryPoint,
+        to: GeometryPoint,
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+    },
+    Rect {
+        upper_left: GeometryPoint,
+        size: [ConrodScalar; 2],
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+        fill: bool,
+    },
+    Path {
+        points: Vec<GeometryPoint>,
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+    },
+    Circle {
+        center: GeometryPoint,
+        radius: ConrodScalar,
+        color: conrod::color::Color,
+        thickness: ConrodScalar,
+        fill: bool,
+        // Number of circumference segments (if rendered coarse, otherwise Conrod default is used)
+        resolution: Option<usize>,
+    },
+    Fill {
+        triangles: Vec<[GeometryPoint; 3]>,
+        color: conrod::color::Color,
+    },
+    Text {
+        text: String,
+        pos: GeometryPoint,
+        size: ConrodScalar,
+        color: conrod::color::Color,
+        justify: conrod::text::Justify,
+        family: String,
+        style: FontStyle,
+    },
+}
+
+impl GeometryPrimitive {
+    pub(crate) fn kind(&self) -> ConrodBackendPrimitive {
+        match self {
+            GeometryPrimitive::Line { .. } => ConrodBackendPrimitive::Line,
+            GeometryPrimitive::Rect { .. } => ConrodBackendPrimitive::Rect,
+            GeometryPrimitive::Path { .. } => ConrodBackendPrimitive::Path,
+            GeometryPrimitive::Circle { .. } => ConrodBackendPrimitive::Circle,
+            GeometryPrimitive::Fill { .. } => ConrodBackendPrimitive::Polygon,
+            GeometryPrimitive::Text { .. } => ConrodBackendPrimitive::Text,
+        }
+    }
+
+    pub(crate) fn points(&self) -> usize {
+        match self {
+            GeometryPrimitive::Line { .. } | GeometryPrimitive::Rect { .. } => 2,
+            GeometryPrimitive::Path { points, .. } => points.len(),
+            GeometryPrimitive::Circle { .. } | GeometryPrimitive::Text { .. } => 1,
+            GeometryPrimitive::Fill { triangles, .. } => triangles.len() * 3,
+        }
+    }
+}
+
+impl ConrodBackendGeometry {
+    /// Create a new empty geometry container
+    ///
+    /// **The same container can be re-used across frames, as it gets cleared every time a builder is created from it.**
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the size of the plot this geometry was built for, in pixels
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Check whether this geometry holds no primitive
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Get the report of all operations that were skipped while building this geometry
+    pub fn skipped(&self) -> &ConrodBackendSkipReport {
+        &self.skipped
+    }
+
+    #[inline(always)]
+    pub(crate) fn prepare(&mut self, size: (u32, u32)) {
+        // Clear previous primitives (this keeps the allocated capacity around for next frame)
+        self.size = size;
+        self.primitives.clear();
+        self.skipped.reset();
+    }
+}
+
+impl<'a> ConrodBackendGeometryBuilder<'a> {
+    /// Create a new geometry builder, with:
+    /// - `geometry`: a mutable reference to the geometry container to fill (any previous content gets cleared)
+    /// - `(plot_width, plot_height)`: the size of your plot in pixels (make sure it matches its parent canvas size)
+    pub fn new(geometry: &'a mut ConrodBackendGeometry, size: (u32, u32)) -> Self {
+        geometry.prepare(size);
+
+        Self {
+            geometry,
+            simplify: GeometrySimplify::default(),
+            stroke_polygons: false,
+            strict: false,
+            scratch: GeometryScratch::default(),
+        }
+    }
+
+    /// Enable strict mode, where unsupported operations return an error instead of being silently ignored
+    ///
+    /// Unsupported operations are the same as in the Conrod backend (ie. pixels, bitmaps and text transforms). Whether strict mode is enabled or not, all unsupported operations are reported in the skip report, which can be acquired from the geometry once built (see `ConrodBackendGeometry::skipped()`).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+
+        self
+    }
+
+    /// Simplify dense geometry, by dropping points that deviate less than a tolerance from a straight line, with:
+    /// - `tolerance`: the maximum deviation in pixels (eg. `0.5` for sub-pixel error), `0.0` disables simplification
+    /// - `paths`: whether paths also get simplified (filled polygons always get simplified)
+    pub fn simplify(mut self, tolerance: ConrodScalar, paths: bool) -> Self {
+        self.simplify.tolerance = tolerance;
+        self.simplify.paths = paths;
+
+        self
+    }
+
+    /// Decimate paths down to their envelope in each pixel column (ie. first, minimum, maximum and last points)
+    pub fn decimate(mut self, decimate: bool) -> Self {
+        self.simplify.decimate = decimate;
+
+        self
+    }
+
+    /// Stroke the outline of filled polygons on top of their fill, as a closed path (using the polygon style color and stroke width)
+    pub fn stroke_polygons(mut self, stroke_polygons: bool) -> Self {
+        self.stroke_polygons = stroke_polygons;
+
+        self
+    }
+
+    #[inline(always)]
+    fn skip(
+        &mut self,
+        primitive: ConrodBackendPrimitive,
+        points: usize,
+        feature: &'static str,
+    ) -> Result<(), DrawingErrorKind<ConrodBackendError>> {
+        // Report skipped operation (this is done in any case, even in strict mode)
+        self.geometry.skipped.count(primitive);
+
+        if self.strict {
+            Err(DrawingErrorKind::DrawingError(
+                ConrodBackendError::UnsupportedFeature {
+                    context: ConrodBackendErrorContext::new(primitive, None, points),
+                    feature,
+                },
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'a> DrawingBackend for ConrodBackendGeometryBuilder<'a> {
+    type ErrorType = ConrodBackendError;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.geometry.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<ConrodBackendError>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<ConrodBackendError>> {
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        _point: BackendCoord,
+        _color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        // Not supported yet (rendering ignored, as in the Conrod backend)
+
+        self.skip(ConrodBackendPrimitive::Pixel, 1, "pixel rendering")
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.geometry.primitives.push(GeometryPrimitive::Line {
+            from: point_from_coord(&from),
+            to: point_from_coord(&to),
+            color: color::Color::from(&style.color()).into(),
+            thickness: style.stroke_width() as ConrodScalar,
+        });
+
+        Ok(())
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let (rect_upper_left, rect_size) = rect_from_corners(&upper_left, &bottom_right);
+
+        if !rect_is_empty(&rect_size, fill) {
+            self.geometry.primitives.push(GeometryPrimitive::Rect {
+                upper_left: rect_upper_left,
+                size: rect_size,
+                color: color::Color::from(&style.color()).into(),
+                thickness: style.stroke_width() as ConrodScalar,
+                fill,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let mut points = Vec::new();
+
+        simplify_path(path, &self.simplify, &mut points);
+
+        self.geometry.primitives.push(GeometryPrimitive::Path {
+            points,
+            color: color::Color::from(&style.color()).into(),
+            thickness: style.stroke_width() as ConrodScalar,
+        });
+
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.geometry.primitives.push(GeometryPrimitive::Circle {
+            center: point_from_coord(&center),
+            radius: radius as ConrodScalar,
+            color: color::Color::from(&style.color()).into(),
+            thickness: style.stroke_width() as ConrodScalar,
+            fill,
+            resolution: None,
+        });
+
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let (mut triangles, polygon_color) =
+            (Vec::new(), color::Color::from(&style.color()).into());
+
+        // Notice: the points buffer is taken from the scratch while triangulating, as the \
+        //   triangulation borrows the rest of the scratch.
+        let mut points = mem::take(&mut self.scratch.points);
+
+        points.clear();
+        points.extend(vert.into_iter().map(|point| point_from_coord(&point)));
+
+        let triangulated = triangulate_polygon(
+            points.iter().copied(),
+            self.simplify.tolerance,
+            None,
+            &mut self.scratch,
+            |triangle| triangles.push(*triangle),
+        );
+
+        self.scratch.points = points;
+
+        triangulated.map_err(DrawingErrorKind::DrawingError)?;
+
+        if !triangles.is_empty() {
+            self.geometry.primitives.push(GeometryPrimitive::Fill {
+                triangles,
+                color: polygon_color,
+            });
+        }
+
+        // Stroke outline on top of the fill (as a closed path)
+        if self.stroke_polygons {
+            let mut ring = Vec::new();
+
+            if close_ring(&self.scratch.points, &mut ring) {
+                self.geometry.primitives.push(GeometryPrimitive::Path {
+                    points: ring,
+                    color: polygon_color,
+                    thickness: style.stroke_width() as ConrodScalar,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_text<S: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &S,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        // Text transforms are not supported by Conrod (text gets rendered without its transform)
+        if !matches!(style.transform(), FontTransform::None) {
+            self.skip(ConrodBackendPrimitive::Text, 1, "text transform")?;
+        }
+
+        self.geometry.primitives.push(GeometryPrimitive::Text {
+            text: text.to_owned(),
+            pos: point_from_coord(&pos),
+            size: text_size(style.size(), None).map_err(DrawingErrorKind::DrawingError)?,
+            color: color::Color::from(&style.color()).into(),
+            justify: justify_from_anchor(style.anchor().h_pos),
+            family: style.family().as_str().to_owned(),
+            style: style.style(),
+        });
+
+        Ok(())
+    }
+
+    fn estimate_text_size<S: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &S,
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        let (text_width_estimated, text_height_estimated) = convert::font_style(text, style.size());
+
+        // Return as (size_on_x, size_on_y)
+        Ok((text_width_estimated as u32, text_height_estimated))
+    }
+
+    fn blit_bitmap(
+        &mut self,
+        _pos: BackendCoord,
+        size: (u32, u32),
+        _src: &[u8],
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        // Not supported yet (rendering ignored, as in the Conrod backend)
+        let points = bitmap_points(size, None).map_err(DrawingErrorKind::DrawingError)?;
+
+        self.skip(ConrodBackendPrimitive::Bitmap, points, "bitmap rendering")
+    }
+}
+
+#[inline(always)]
+pub(crate) fn bitmap_points(
+    (width, height): (u32, u32),
+    parent: Option<conrod::widget::Id>,
+) -> Result<usize, ConrodBackendError> {
+    // Notice: the number of pixels in a bitmap may not fit in a 'usize' on 32 bits targets
+    (width as usize)
+        .checked_mul(height as usize)
+        .ok_or(ConrodBackendError::InvalidGeometry {
+            context: ConrodBackendErrorContext::new(ConrodBackendPrimitive::Bitmap, parent, 0),
+            reason: "bitmap size overflows",
+        })
+}
+
+pub(crate) fn simplify_path<I>(
+    path: I,
+    simplify: &GeometrySimplify,
+    simplified_path: &mut Vec<GeometryPoint>,
+) where
+    I: IntoIterator<Item = BackendCoord>,
+{
+    let points = path.into_iter().map(|point| point_from_coord(&point));
+
+    // Notice: the simplified path buffer is cleared and then filled, as to re-use its capacity
+    simplified_path.clear();
+
+    match (simplify.decimate, simplify.paths) {
+        (true, true) => simplified_path.extend(path::PathReducer::from(
+            path::PathDecimator::from(points),
+            simplify.tolerance,
+        )),
+        (true, false) => simplified_path.extend(path::PathDecimator::from(points)),
+        (false, true) => {
+            simplified_path.extend(path::PathReducer::from(points, simplify.tolerance))
+        }
+        (false, false) => simplified_path.extend(points),
+    }
+}
+
+pub(crate) fn bounds_of<'p, P>(points: P, margin: ConrodScalar) -> [GeometryPoint; 2]
+where
+    P: IntoIterator<Item = &'p GeometryPoint>,
+{
+    let (start, end) = points.into_iter().fold(
+        (
+            [ConrodScalar::INFINITY, ConrodScalar::INFINITY],
+            [ConrodScalar::NEG_INFINITY, ConrodScalar::NEG_INFINITY],
+        ),
+        |(start, end), point| {
+            (
+                [start[0].min(point[0]), start[1].min(point[1])],
+                [end[0].max(point[0]), end[1].max(point[1])],
+            )
+        },
+    );
+
+    [
+        [start[0] - margin, start[1] - margin],
+        [end[0] + margin, end[1] + margin],
+    ]
+}
+
+#[inline(always)]
+pub(crate) fn point_from_coord(coord: &BackendCoord) -> GeometryPoint {
+    [coord.0 as ConrodScalar, coord.1 as ConrodScalar]
+}
+
+#[inline(always)]
+pub(crate) fn justify_from_anchor(h_pos: text_anchor::HPos) -> conrod::text::Justify {
+    match h_pos {
+        text_anchor::HPos::Left => conrod::text::Justify::Left,
+        text_anchor::HPos::Right => conrod::text::Justify::Right,
+        text_anchor::HPos::Center => conrod::text::Justify::Center,
+    }
+}
+
+#[inline(always)]
+pub(crate) fn rect_from_corners(
+    upper_left: &BackendCoord,
+    bottom_right: &BackendCoord,
+) -> (GeometryPoint, [ConrodScalar; 2]) {
+    // Notice: corners get normalized, as Plotters may pass swapped corners (eg. for negative \
+    //   histogram bars, or reversed axes). Size is computed using floating point numbers, as \
+    //   subtracting extreme corner coordinates (eg. far outside of the chart range) would \
+    //   overflow integers.
+    let (from, to) = (point_from_coord(upper_left), point_from_coord(bottom_right));
+
+    (
+        [from[0].min(to[0]), from[1].min(to[1])],
+        [(to[0] - from[0]).abs(), (to[1] - from[1]).abs()],
+    )
+}
+
+#[inline(always)]
+pub(crate) fn rect_is_empty(size: &[ConrodScalar; 2], fill: bool) -> bool {
+    // Filled rectangles cover pixels up to their bottom-right corner (excluded), as in the \
+    //   Bitmap backend, thus zero-area filled rectangles cover no pixel at all. Outlined \
+    //   rectangles always cover pixels, as their outline goes through corner pixels.
+    fill && (size[0] <= 0.0 || size[1] <= 0.0)
+}
+
+pub(crate) fn rect_outline(
+    upper_left: &GeometryPoint,
+    size: &[ConrodScalar; 2],
+    thickness: ConrodScalar,
+) -> [Option<(GeometryPoint, [ConrodScalar; 2])>; 4] {
+    // Thick outlines are stroked side by side, as in the Bitmap backend (which does not join \
+    //   thick lines at corners)
+    if thickness >= 2.0 {
+        let (left, top, right, bottom) = (
+            upper_left[0],
+            upper_left[1],
+            upper_left[0] + size[0],
+            upper_left[1] + size[1],
+        );
+
+        return [
+            rect_outline_side([left, top], [left, bottom], thickness),
+            rect_outline_side([left, top], [right, top], thickness),
+            rect_outline_side([left, bottom], [right, bottom], thickness),
+            rect_outline_side([right, top], [right, bottom], thickness),
+        ];
+    }
+
+    // Build the outline as a frame of filled rectangles, where the outline stroke is centered \
+    //   on the centers of the corner pixels, as in the Bitmap backend (which draws outlines as \
+    //   4 lines going through corner pixels, including corners themselves).
+    let half_thickness = thickness / 2.0;
+
+    let (outer_start, outer_size) = (
+        [
+            upper_left[0] + 0.5 - half_thickness,
+            upper_left[1] + 0.5 - half_thickness,
+        ],
+        [size[0] + thickness, size[1] + thickness],
+    );
+
+    // Outline is thicker than the rectangle itself? Fill the whole outer rectangle.
+    if size[0] <= thickness || size[1] <= thickness {
+        return [Some((outer_start, outer_size)), None, None, None];
+    }
+
+    let side_height = outer_size[1] - 2.0 * thickness;
+
+    [
+        // Top side
+        Some((outer_start, [outer_size[0], thickness])),
+        // Bottom side
+        Some((
+            [outer_start[0], outer_start[1] + outer_size[1] - thickness],
+            [outer_size[0], thickness],
+        )),
+        // Left side
+        Some((
+            [outer_start[0], outer_start[1] + thickness],
+            [thickness, side_height],
+        )),
+        // Right side
+        Some((
+            [
+                outer_start[0] + outer_size[0] - thickness,
+                outer_start[1] + thickness,
+            ],
+            [thickness, side_height],
+        )),
+    ]
+}
+
+#[allow(clippy::float_cmp)]
+fn rect_outline_side(
+    from: GeometryPoint,
+    to: GeometryPoint,
+    thickness: ConrodScalar,
+) -> Option<(GeometryPoint, [ConrodScalar; 2])> {
+    // Notice: the Bitmap backend strokes each side as a polygon, spanning half the thickness on \
+    //   both sides of the side line (truncated towards zero to integer pixels). This polygon is \
+    //   then filled by sweeping lines along its longest dimension, including both boundaries, \
+    //   except for the last sweep line, which gets dropped if the polygon is wider than 1 pixel \
+    //   across it. Sides without any length are not drawn at all.
+    let half_thickness = thickness / 2.0;
+
+    let (start, mut end) = if from[0] == to[0] {
+        if from[1] == to[1] {
+            return None;
+        }
+
+        (
+            [(from[0] - half_thickness).trunc(), from[1]],
+            [(from[0] + half_thickness).trunc(), to[1]],
+        )
+    } else {
+        (
+            [from[0], (from[1] - half_thickness).trunc()],
+            [to[0], (from[1] + half_thickness).trunc()],
+        )
+    };
+
+    if end[0] - start[0] > end[1] - start[1] {
+        if end[1] - start[1] > 1.0 {
+            end[0] -= 1.0;
+        }
+    } else if end[0] - start[0] > 1.0 {
+        end[1] -= 1.0;
+    }
+
+    // Sides cover pixels from their start to their end, both included
+    Some((start, [end[0] - start[0] + 1.0, end[1] - start[1] + 1.0]))
+}
+
+pub(crate) fn line_quad(
+    from: &GeometryPoint,
+    to: &GeometryPoint,
+    thickness: ConrodScalar,
+) -> Option<[GeometryPoint; 4]> {
+    // Lines without any thickness are not drawn, as in the Bitmap backend
+    if thickness <= 0.0 {
+        return None;
+    }
+
+    // Notice: hairlines (ie. lines thinner than a pixel) are widened to a pixel, as they would \
+    //   otherwise disappear when rasterized.
+    let width = thickness.max(LINE_WIDTH_MINIMUM);
+
+    let offset = stroke_offset(width);
+
+    // Hairlines are extended by half a pixel on both ends (so that they cover their end \
+    //   pixels), while thicker lines end flat on their end points
+    let extend = if width <= 1.0 { 0.5 } else { 0.0 };
+
+    let delta = [to[0] - from[0], to[1] - from[1]];
+    let length = delta[0].hypot(delta[1]);
+
+    let direction = if length > 0.0 {
+        [delta[0] / length, delta[1] / length]
+    } else if extend > 0.0 {
+        // Zero-length hairlines still cover their pixel
+        [1.0, 0.0]
+    } else {
+        return None;
+    };
+
+    let (start, end) = (
+        [
+            from[0] + offset - direction[0] * extend,
+            from[1] + offset - direction[1] * extend,
+        ],
+        [
+            to[0] + offset + direction[0] * extend,
+            to[1] + offset + direction[1] * extend,
+        ],
+    );
+
+    let normal = [-direction[1] * width / 2.0, direction[0] * width / 2.0];
+
+    Some([
+        [start[0] + normal[0], start[1] + normal[1]],
+        [end[0] + normal[0], end[1] + normal[1]],
+        [end[0] - normal[0], end[1] - normal[1]],
+        [start[0] - normal[0], start[1] - normal[1]],
+    ])
+}
+
+#[inline(always)]
+pub(crate) fn stroke_offset(thickness: ConrodScalar) -> ConrodScalar {
+    // Align strokes with odd widths on pixel centers, so that they cover whole pixels (as in the \
+    //   Bitmap backend, where a 1 pixel wide line covers the pixels from its start point to its \
+    //   end point, both included)
+    if thickness.max(LINE_WIDTH_MINIMUM).round() as i64 % 2 == 1 {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+pub(crate) fn stroke_points(
+    points: &[GeometryPoint],
+    thickness: ConrodScalar,
+) -> impl Iterator<Item = GeometryPoint> + Clone + '_ {
+    // Align open paths on pixel centers as lines and rings are (for odd widths)
+    let offset = stroke_offset(thickness);
+
+    points
+        .iter()
+        .map(move |point| [point[0] + offset, point[1] + offset])
+}
+
+#[allow(clippy::float_cmp)]
+pub(crate) fn close_ring(points: &[GeometryPoint], ring: &mut Vec<GeometryPoint>) -> bool {
+    // Join the last vertex back to the first one (if not already done), so that the resulting \
+    //   path is stroked as a closed ring
+    ring.clear();
+
+    match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() > 2 => {
+            ring.extend_from_slice(points);
+
+            if first != last {
+                ring.push(*first);
+            }
+
+            true
+        }
+        _ => false,
+    }
+}
+
+#[inline(always)]
+#[allow(clippy::float_cmp)]
+pub(crate) fn path_is_closed(points: &[GeometryPoint]) -> bool {
+    points.len() > 3 && points.first() == points.last()
+}
+
+#[allow(clippy::float_cmp)]
+pub(crate) fn stroke_ring<F>(points: &[GeometryPoint], thickness: ConrodScalar, mut on_triangle: F)
+where
+    F: FnMut(&[GeometryPoint; 3]),
+{
+    // Stroke a closed ring as segment quads, joined with miter joins on all vertices \
+    //   (including the closing vertex), falling back to bevel joins on sharp angles
+    let width = thickness.max(LINE_WIDTH_MINIMUM);
+    let (half_width, offset) = (width / 2.0, stroke_offset(width));
+
+    // Acquire ring segments along with their directions (empty segments have none), aligned on \
+    //   pixel centers as lines are
+    let ring = &points[..(points.len() - 1)];
+
+    let segment = |index: usize| {
+        let (from, to) = (ring[index % ring.len()], ring[(index + 1) % ring.len()]);
+        let (from, to) = (
+            [from[0] + offset, from[1] + offset],
+            [to[0] + offset, to[1] + offset],
+        );
+        let length = (to[0] - from[0]).hypot(to[1] - from[1]);
+
+        if length > 0.0 {
+            Some((
+                from,
+                to,
+                [(to[0] - from[0]) / length, (to[1] - from[1]) / length],
+            ))
+        } else {
+            None
+        }
+    };
+
+    for index in 0..ring.len() {
+        let (from, to, direction) = match segment(index) {
+            Some(segment) => segment,
+            None => continue,
+        };
+
+        let normal = [-direction[1] * half_width, direction[0] * half_width];
+
+        // Render segment quad
+        let quad = [
+            [from[0] + normal[0], from[1] + normal[1]],
+            [to[0] + normal[0], to[1] + normal[1]],
+            [to[0] - normal[0], to[1] - normal[1]],
+            [from[0] - normal[0], from[1] - normal[1]],
+        ];
+
+        on_triangle(&[quad[0], quad[1], quad[2]]);
+        on_triangle(&[quad[0], quad[2], quad[3]]);
+
+        // Render join with next non-empty segment, on the outer side of the turn
+        let next_direction = (1..=ring.len())
+            .find_map(|offset| segment(index + offset))
+            .map_or(direction, |next_segment| next_segment.2);
+
+        let cross = direction[0] * next_direction[1] - direction[1] * next_direction[0];
+        let dot = direction[0] * next_direction[0] + direction[1] * next_direction[1];
+
+        if cross != 0.0 {
+            let side = if cross > 0.0 { -1.0 } else { 1.0 };
+
+            let (outer, next_outer) = (
+                [to[0] + side * normal[0], to[1] + side * normal[1]],
+                [
+                    to[0] - side * next_direction[1] * half_width,
+                    to[1] + side * next_direction[0] * half_width,
+                ],
+            );
+
+            on_triangle(&[to, outer, next_outer]);
+
+            // Extend bevel join to a miter join, if the miter is not too long
+            let miter_ratio = (2.0 / (1.0 + dot)).sqrt();
+
+            if miter_ratio <= STROKE_MITER_LIMIT {
+                let bisector = [
+                    (outer[0] + next_outer[0]) / 2.0 - to[0],
+                    (outer[1] + next_outer[1]) / 2.0 - to[1],
+                ];
+                let bisector_length = bisector[0].hypot(bisector[1]);
+
+                if bisector_length > 0.0 {
+                    let scale = half_width * miter_ratio / bisector_length;
+
+                    on_triangle(&[
+                        outer,
+                        [to[0] + bisector[0] * scale, to[1] + bisector[1] * scale],
+                        next_outer,
+                    ]);
+                }
+            }
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn text_size(
+    size: ConrodScalar,
+    parent: Option<conrod::widget::Id>,
+) -> Result<ConrodScalar, ConrodBackendError> {
+    if size.is_finite() {
+        Ok(size)
+    } else {
+        Err(ConrodBackendError::InvalidGeometry {
+            context: ConrodBackendErrorContext::new(ConrodBackendPrimitive::Text, parent, 1),
+            reason: "font size is not a finite number",
+        })
+    }
+}
+
+pub(crate) fn triangulate_polygon<I, F>(
+    vert: I,
+    tolerance: ConrodScalar,
+    parent: Option<conrod::widget::Id>,
+    scratch: &mut GeometryScratch,
+    mut on_triangle: F,
+) -> Result<(), ConrodBackendError>
+where
+    I: IntoIterator<Item = GeometryPoint>,
+    F: FnMut(&[GeometryPoint; 3]),
+{
+    // Paint a simplified path, where empty areas are removed and un-necessary points are \
+    //   cleared. This is required for triangulation to work properly, and it reduces \
+    //   the number of triangles on screen to a strict minimum.
+    // Notice: points that deviate less than the tolerance from a straight line are also \
+    //   cleared, if a tolerance is set (this catches diagonal and near-collinear points).
+    // Notice: all intermediate buffers are taken from the scratch, so that no allocation is \
+    //   done on the Rust side once the scratch buffers are large enough.
+    let GeometryScratch {
+        simplified: simplified_path,
+        splitter: shape_splitter,
+        nester: shape_nester,
+        triangulator,
+        ..
+    } = scratch;
+
+    simplified_path.clear();
+    simplified_path.extend(path::PathReducer::from(
+        path::PathSimplifier::from(vert.into_iter()),
+        tolerance,
+    ));
+
+    // Fast path: fill x-monotone polygons (eg. area series, or confidence bands) directly, \
+    //   without splitting shapes and triangulating them
+    if let Ok(monotone_fill) = monotone::MonotoneFill::try_from(simplified_path) {
+        monotone_fill.triangulate(&mut on_triangle);
+
+        return Ok(());
+    }
+
+    // Find closed shapes (eg. when the plot area goes from positive to negative, we need \
+    //   to split the path into two distinct paths, otherwise we will not be able to \
+    //   triangulate properly, and thus we will not be able to fill the shape)
+    if shape_splitter.load(simplified_path).is_ok() {
+        // Triangulate the polygon points, giving back a list of triangles that can be \
+        //   filled into a contiguous area.
+        // Notice: this method takes into account concave shapes
+        // Notice: nested shapes are triangulated as holes of their container (eg. for donut \
+        //   shapes such as confidence bands that wrap around a line).
+        let shapes = shape_splitter.collect();
+
+        shape_nester.collect(shapes);
+
+        for outline in shape_nester.outlines(shapes) {
+            let (shape_points, shape_holes) = (
+                &shapes[outline],
+                shape_nester
+                    .holes(outline)
+                    .map(|hole| shapes[hole].as_slice()),
+            );
+
+            let triangulation_error =
+                |err: triangulate::TriangulateError| ConrodBackendError::TriangulationFailed {
+                    context: ConrodBackendErrorContext::new(
+                        ConrodBackendPrimitive::Polygon,
+                        parent,
+                        shape_points.len() + shape_holes.clone().map(<[_]>::len).sum::<usize>(),
+                    ),
+                    reason: err.0,
+                };
+
+            let triangles = triangulator
+                .triangulate(shape_points.iter(), shape_holes.clone())
+                .map_err(triangulation_error)?;
+
+            for index in 0..triangles.size() {
+                on_triangle(
+                    &triangles
+                        .get_triangle(index)
+                        .map_err(triangulation_error)?
+                        .points,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use plotters::prelude::{BitMapBackend, Color, ShapeStyle, BLACK};
+    use plotters_backend::DrawingBackend;
+
+    const SIZE: (u32, u32) = (64, 64);
+
+    fn rasterize_bitmap(
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        thickness: u32,
+        fill: bool,
+    ) -> Vec<bool> {
+        let mut buffer = vec![255; (SIZE.0 * SIZE.1 * 3) as usize];
+
+        {
+            let mut backend = BitMapBackend::with_buffer(&mut buffer, SIZE);
+
+            backend
+                .draw_rect(
+                    upper_left,
+                    bottom_right,
+                    &ShapeStyle::from(&BLACK).stroke_width(thickness),
+                    fill,
+                )
+                .unwrap();
+            backend.present().unwrap();
+        }
+
+        buffer.chunks(3).map(|pixel| pixel[0] == 0).collect()
+    }
+
+    fn rasterize_rects(rects: &[(GeometryPoint, [ConrodScalar; 2])]) -> Vec<bool> {
+        // Pixels are covered if their center lies within a rectangle (as when rasterized on GPU)
+        (0..(SIZE.0 * SIZE.1))
+            .map(|index| {
+                let center = [
+                    (index % SIZE.0) as ConrodScalar + 0.5,
+                    (index / SIZE.0) as ConrodScalar + 0.5,
+                ];
+
+                rects.iter().any(|(upper_left, size)| {
+                    center[0] >= upper_left[0]
+                        && center[0] < upper_left[0] + size[0]
+                        && center[1] >= upper_left[1]
+                        && center[1] < upper_left[1] + size[1]
+                })
+            })
+            .collect()
+    }
+
+    fn rasterize(
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        thickness: u32,
+        fill: bool,
+    ) -> Vec<bool> {
+        let (rect_upper_left, rect_size) = rect_from_corners(&upper_left, &bottom_right);
+
+        if rect_is_empty(&rect_size, fill) {
+            rasterize_rects(&[])
+        } else if fill {
+            rasterize_rects(&[(rect_upper_left, rect_size)])
+        } else {
+            let outline: Vec<_> =
+                rect_outline(&rect_upper_left, &rect_size, thickness as ConrodScalar)
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect();
+
+            rasterize_rects(&outline)
+        }
+    }
+
+    fn assert_rasterizes_like_bitmap(
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        thickness: u32,
+        fill: bool,
+    ) {
+        assert!(
+            rasterize(upper_left, bottom_right, thickness, fill)
+                == rasterize_bitmap(upper_left, bottom_right, thickness, fill),
+            "rect {:?} to {:?} (thickness: {}, fill: {}) does not match Bitmap pixels",
+            upper_left,
+            bottom_right,
+            thickness,
+            fill
+        );
+    }
+
+    #[test]
+    fn it_rasterizes_outlines_like_bitmap() {
+        // Regular and swapped corners
+        assert_rasterizes_like_bitmap((10, 12), (40, 30), 1, false);
+        assert_rasterizes_like_bitmap((40, 30), (10, 12), 1, false);
+        assert_rasterizes_like_bitmap((10, 30), (40, 12), 1, false);
+
+        // Zero-width, zero-height and single pixel rectangles
+        assert_rasterizes_like_bitmap((20, 10), (20, 40), 1, false);
+        assert_rasterizes_like_bitmap((10, 20), (40, 20), 1, false);
+        assert_rasterizes_like_bitmap((20, 20), (20, 20), 1, false);
+
+        // Rectangles as small as their outline
+        assert_rasterizes_like_bitmap((20, 20), (21, 22), 1, false);
+    }
+
+    #[test]
+    fn it_rasterizes_thick_outlines_like_bitmap() {
+        for thickness in [2, 3, 4, 5, 8] {
+            assert_rasterizes_like_bitmap((10, 12), (40, 30), thickness, false);
+            assert_rasterizes_like_bitmap((40, 30), (10, 12), thickness, false);
+            assert_rasterizes_like_bitmap((10, 30), (40, 12), thickness, false);
+            assert_rasterizes_like_bitmap((20, 10), (20, 40), thickness, false);
+            assert_rasterizes_like_bitmap((10, 20), (40, 20), thickness, false);
+            assert_rasterizes_like_bitmap((20, 20), (20, 20), thickness, false);
+            assert_rasterizes_like_bitmap((20, 20), (21, 22), thickness, false);
+            assert_rasterizes_like_bitmap((20, 20), (22, 23), thickness, false);
+
+            // Rectangles along the plot border (where stroke bounds are truncated towards zero)
+            assert_rasterizes_like_bitmap((0, 0), (30, 20), thickness, false);
+            assert_rasterizes_like_bitmap((-3, -5), (30, 20), thickness, false);
+        }
+    }
+
+    #[test]
+    fn it_rasterizes_fills_like_bitmap() {
+        assert_rasterizes_like_bitmap((10, 12), (40, 30), 1, true);
+        assert_rasterizes_like_bitmap((40, 30), (10, 12), 1, true);
+        assert_rasterizes_like_bitmap((10, 30), (40, 12), 1, true);
+        assert_rasterizes_like_bitmap((20, 10), (20, 40), 1, true);
+        assert_rasterizes_like_bitmap((20, 20), (20, 20), 1, true);
+    }
+
+    #[test]
+    fn it_aligns_odd_width_paths_on_pixel_centers() {
+        let points = [[10.0, 10.0], [20.0, 15.0]];
+
+        for (thickness, offset) in [(0.0, 0.5), (1.0, 0.5), (2.0, 0.0), (3.0, 0.5)] {
+            assert_eq!(
+                stroke_points(&points, thickness).collect::<Vec<_>>(),
+                vec![
+                    [10.0 + offset, 10.0 + offset],
+                    [20.0 + offset, 15.0 + offset]
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn it_converts_extreme_coordinates() {
+        assert_eq!(
+            point_from_coord(&(i32::MIN, i32::MAX)),
+            [i32::MIN as ConrodScalar, i32::MAX as ConrodScalar]
+        );
+    }
+
+    #[test]
+    fn it_builds_rects_from_extreme_corners() {
+        let extent = u32::MAX as ConrodScalar;
+
+        // Regular, swapped and partially swapped corners all give the same rectangle
+        for (upper_left, bottom_right) in [
+            ((i32::MIN, i32::MIN), (i32::MAX, i32::MAX)),
+            ((i32::MAX, i32::MAX), (i32::MIN, i32::MIN)),
+            ((i32::MIN, i32::MAX), (i32::MAX, i32::MIN)),
+        ] {
+            assert_eq!(
+                rect_from_corners(&upper_left, &bottom_right),
+                (
+                    [i32::MIN as ConrodScalar, i32::MIN as ConrodScalar],
+                    [extent, extent]
+                )
+            );
+        }
+
+        assert_eq!(
+            rect_from_corners(&(i32::MAX, 0), &(i32::MAX, 0)),
+            ([i32::MAX as ConrodScalar, 0.0], [0.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn it_reports_skipped_operations_when_building() {
+        let mut geometry = ConrodBackendGeometry::new();
+
+        {
+            let mut builder = ConrodBackendGeometryBuilder::new(&mut geometry, SIZE);
+
+            builder
+                .draw_pixel((1, 1), BLACK.to_backend_color())
+                .unwrap();
+            builder
+                .draw_pixel((2, 1), BLACK.to_backend_color())
+                .unwrap();
+            builder
+                .blit_bitmap((0, 0), (u32::MAX, u32::MAX), &[])
+                .unwrap();
+        }
+
+        assert_eq!(
+            geometry.skipped(),
+            &ConrodBackendSkipReport {
+                pixels: 2,
+                bitmaps: 1,
+                text_transforms: 0,
+            }
+        );
+        assert!(geometry.is_empty());
+
+        // Strict mode fails on unsupported operations, but still reports them
+        let mut builder = ConrodBackendGeometryBuilder::new(&mut geometry, SIZE).strict(true);
+
+        assert!(matches!(
+            builder.blit_bitmap((0, 0), (2, 3), &[]),
+            Err(DrawingErrorKind::DrawingError(
+                ConrodBackendError::UnsupportedFeature {
+                    context: ConrodBackendErrorContext {
+                        primitive: ConrodBackendPrimitive::Bitmap,
+                        points: 6,
+                        ..
+                    },
+                    ..
+                }
+            ))
+        ));
+        assert_eq!(builder.geometry.skipped().bitmaps, 1);
+    }
+}
@@ -6,55 +6,105 @@
 
 use conrod_core as conrod;
 
+use crate::geometry::GeometryScratch;
+use crate::report::{ConrodBackendCullReport, ConrodBackendRenderReport, ConrodBackendSkipReport};
+
 const BACKEND_GRAPH_RESIZE_CHUNK: usize = 100;
 
 /// The re-usable graph of Conrod widget IDs, to be re-used for each plot draw (building it is expensive, re-using it is cheap; so build it once and re-use it across loop calls)
 pub struct ConrodBackendReusableGraph {
-    pub(crate) line: ConrodBackendReusableGraphAtom,
-    pub(crate) rect: ConrodBackendReusableGraphAtom,
+    pub(crate) mesh: ConrodBackendReusableGraphAtom,
     pub(crate) path: ConrodBackendReusableGraphAtom,
     pub(crate) circle: ConrodBackendReusableGraphAtom,
     pub(crate) text: ConrodBackendReusableGraphAtom,
-    pub(crate) fill: ConrodBackendReusableGraphAtom,
+    pub(crate) overlay_mesh: ConrodBackendReusableGraphAtom,
+    pub(crate) overlay_text: ConrodBackendReusableGraphAtom,
+    pub(crate) skipped: ConrodBackendSkipReport,
+    pub(crate) culled: ConrodBackendCullReport,
+    pub(crate) rendered: ConrodBackendRenderReport,
+    pub(crate) batch: ConrodBackendReusableGraphBatch,
+    pub(crate) overlay_batch: ConrodBackendReusableGraphBatch,
+    pub(crate) scratch: GeometryScratch,
 }
 
 pub(crate) struct ConrodBackendReusableGraphAtom(conrod::widget::id::List, usize);
 
+#[derive(Default)]
+pub(crate) struct ConrodBackendReusableGraphBatch {
+    pub(crate) triangles: Vec<conrod::widget::triangles::Triangle<conrod::position::Point>>,
+    pub(crate) color: Option<conrod::color::Color>,
+}
+
 impl ConrodBackendReusableGraph {
     /// Build a new Conrod backend re-usable graph of widget identifiers
     ///
     /// **This should be put outside of your drawer loop and built once; failing to do so will result in heavy CPU usage due to the graph being rebuilt for every frame!**
     pub fn build() -> Self {
         Self {
-            line: ConrodBackendReusableGraphAtom::new(),
-            rect: ConrodBackendReusableGraphAtom::new(),
+            mesh: ConrodBackendReusableGraphAtom::new(),
             path: ConrodBackendReusableGraphAtom::new(),
             circle: ConrodBackendReusableGraphAtom::new(),
             text: ConrodBackendReusableGraphAtom::new(),
-            fill: ConrodBackendReusableGraphAtom::new(),
+            overlay_mesh: ConrodBackendReusableGraphAtom::new(),
+            overlay_text: ConrodBackendReusableGraphAtom::new(),
+            skipped: ConrodBackendSkipReport::default(),
+            culled: ConrodBackendCullReport::default(),
+            rendered: ConrodBackendRenderReport::default(),
+            batch: ConrodBackendReusableGraphBatch::default(),
+            overlay_batch: ConrodBackendReusableGraphBatch::default(),
+            scratch: GeometryScratch::default(),
         }
     }
 
+    /// Get the report of all operations that were skipped while drawing the last frame
+    pub fn skipped(&self) -> &ConrodBackendSkipReport {
+        &self.skipped
+    }
+
+    /// Get the report of all primitives that were culled while drawing the last frame
+    pub fn culled(&self) -> &ConrodBackendCullReport {
+        &self.culled
+    }
+
+    /// Get the report of all primitives that were rendered while drawing the last frame, along with their rendering cost (eg. to feed a debug overlay, or performance regression tests)
+    pub fn rendered(&self) -> &ConrodBackendRenderReport {
+        &self.rendered
+    }
+
     #[inline(always)]
     pub(crate) fn prepare(&mut self) {
         // Notice: destructuring is used there as a safety measure, so that no field is \
         //   forgotten, which could be dangerous (ie. risk of memory leak).
         let Self {
-            line,
-            rect,
+            mesh,
             path,
             circle,
             text,
-            fill,
+            overlay_mesh,
+            overlay_text,
+            skipped,
+            culled,
+            rendered,
+            batch,
+            overlay_batch,
+            scratch: _,
         } = self;
 
         // Proceed all resets
-        line.reset();
-        rect.reset();
+        mesh.reset();
         path.reset();
         circle.reset();
         text.reset();
-        fill.reset();
+        overlay_mesh.reset();
+        overlay_text.reset();
+        skipped.reset();
+        culled.reset();
+        rendered.reset();
+        batch.reset();
+        overlay_batch.reset();
+
+        // Notice: scratch buffers do not need to be reset, as they get cleared upon use (this \
+        //   keeps their allocated capacity around across frames).
     }
 }
 
@@ -94,3 +144,34 @@ impl ConrodBackendReusableGraphAtom {
         self.1 = 0;
     }
 }
+
+impl ConrodBackendReusableGraphBatch {
+    #[inline(always)]
+    pub(crate) fn bounds(&self) -> conrod::Rect {
+        let bounds = self
+            .triangles
+            .iter()
+            .flat_map(|triangle| triangle.0.iter())
+            .fold(
+                [
+                    [conrod::position::Scalar::INFINITY; 2],
+                    [conrod::position::Scalar::NEG_INFINITY; 2],
+                ],
+                |bounds, point| {
+                    [
+                        [bounds[0][0].min(point[0]), bounds[0][1].min(point[1])],
+                        [bounds[1][0].max(point[0]), bounds[1][1].max(point[1])],
+                    ]
+                },
+            );
+
+        conrod::Rect::from_corners(bounds[0], bounds[1])
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        // Clear pending triangles (this keeps allocated capacity around for next frame)
+        self.triangles.clear();
+        self.color = None;
+    }
+}
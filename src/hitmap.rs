@@ -0,0 +1,542 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use conrod_core::position::Scalar as ConrodScalar;
+use plotters_backend::BackendCoord;
+
+use crate::geometry::GeometryPoint;
+
+const HITMAP_CELL_SIZE: ConrodScalar = 16.0;
+
+type HitmapCell = (i32, i32);
+
+/// The spatial index of primitives drawn in the current frame, used for hit-testing (eg. tooltips and selection)
+///
+/// Only primitives drawn while a series identifier is set get indexed. Series identifiers are \
+///   provided by the caller, eg. by calling `set_series()` before drawing each series with \
+///   Plotters, and then `set_series(None)` once done.
+///
+/// **This should be put outside of your drawer loop and built once, as re-using it avoids re-allocating the index for every frame.**
+#[derive(Default)]
+pub struct ConrodBackendHitmap {
+    series: Cell<Option<u64>>,
+    index: RefCell<HitmapIndex>,
+}
+
+/// A primitive found when hit-testing a `ConrodBackendHitmap`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConrodBackendHit {
+    /// The series identifier that was set when the primitive was drawn
+    pub series: u64,
+    /// The index of the primitive within its series (in drawing order, eg. the index of the point in a `PointSeries`)
+    pub primitive: usize,
+    /// The index of the nearest vertex within the primitive (eg. the index of the point in a `LineSeries` path)
+    pub vertex: usize,
+    /// The nearest position on the primitive, in backend pixel coordinates
+    pub position: (ConrodScalar, ConrodScalar),
+    /// The distance between the queried position and the primitive, in pixels
+    pub distance: ConrodScalar,
+}
+
+#[derive(Default)]
+struct HitmapIndex {
+    cells_max: HitmapCell,
+    entries: Vec<HitmapEntry>,
+    cells: HashMap<HitmapCell, Vec<usize>>,
+    counters: HashMap<u64, usize>,
+}
+
+struct HitmapEntry {
+    series: u64,
+    primitive: usize,
+    vertex: usize,
+    shape: HitmapShape,
+}
+
+enum HitmapShape {
+    Segment([GeometryPoint; 2], ConrodScalar),
+    Circle(GeometryPoint, ConrodScalar),
+    Rect(GeometryPoint, GeometryPoint),
+}
+
+impl ConrodBackendHitmap {
+    /// Create a new empty hitmap
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the series identifier that will tag all primitives drawn from now on (`None` disables indexing)
+    pub fn set_series(&self, series: Option<u64>) {
+        self.series.set(series);
+    }
+
+    /// Find the nearest primitive to a position, with:
+    /// - `position`: the position to hit-test, in backend pixel coordinates (eg. as returned by `ConrodBackend::mouse_position()`)
+    /// - `max_distance`: the maximum distance in pixels between the position and the primitive
+    ///
+    /// If several primitives are at the same distance, the one drawn last (ie. on top) is returned.
+    pub fn nearest(
+        &self,
+        position: BackendCoord,
+        max_distance: ConrodScalar,
+    ) -> Option<ConrodBackendHit> {
+        let index = self.index.borrow();
+        let point = [position.0 as ConrodScalar, position.1 as ConrodScalar];
+
+        // Notice: the index of the nearest entry is retained, so that ties get resolved in favor \
+        //   of the primitive drawn last (ie. the one on top), whatever the cell it was found in.
+        let mut nearest: Option<(usize, ConrodBackendHit)> = None;
+
+        // Only check entries in cells that are within the maximum distance
+        let (cell_start, cell_end) = (
+            index.cell_of(&[point[0] - max_distance, point[1] - max_distance]),
+            index.cell_of(&[point[0] + max_distance, point[1] + max_distance]),
+        );
+
+        for cell_x in cell_start.0..=cell_end.0 {
+            for cell_y in cell_start.1..=cell_end.1 {
+                if let Some(cell_entries) = index.cells.get(&(cell_x, cell_y)) {
+                    for entry_index in cell_entries {
+                        let entry = &index.entries[*entry_index];
+                        let (entry_position, entry_distance, entry_vertex) =
+                            entry.shape.nearest(&point);
+
+                        if entry_distance <= max_distance
+                            && nearest.map_or(true, |(nearest_index, hit)| {
+                                entry_distance < hit.distance
+                                    || (entry_distance == hit.distance
+                                        && *entry_index > nearest_index)
+                            })
+                        {
+                            nearest = Some((
+                                *entry_index,
+                                ConrodBackendHit {
+                                    series: entry.series,
+                                    primitive: entry.primitive,
+                                    vertex: entry.vertex + entry_vertex,
+                                    position: (entry_position[0], entry_position[1]),
+                                    distance: entry_distance,
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        nearest.map(|(_, hit)| hit)
+    }
+
+    #[inline(always)]
+    pub(crate) fn prepare(&self, size: (u32, u32)) {
+        // Clear previous frame entries (this keeps allocated capacity around for next frame)
+        self.series.set(None);
+        self.index.borrow_mut().clear(size);
+    }
+
+    #[inline(always)]
+    pub(crate) fn skip_primitive(&self) {
+        // Notice: primitives that are not drawn (eg. culled) still consume their index within \
+        //   their series, so that indices of following primitives match their drawing order.
+        if let Some(series) = self.series.get() {
+            self.index.borrow_mut().next_primitive(series);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn insert_path<'p, P>(&self, points: P, thickness: ConrodScalar)
+    where
+        P: IntoIterator<Item = &'p GeometryPoint>,
+    {
+        if let Some(series) = self.series.get() {
+            let mut index = self.index.borrow_mut();
+            let primitive = index.next_primitive(series);
+
+            let mut last_point: Option<&GeometryPoint> = None;
+
+            for (vertex, point) in points.into_iter().enumerate() {
+                if let Some(last_point) = last_point {
+                    index.insert(HitmapEntry {
+                        series,
+                        primitive,
+                        vertex: vertex - 1,
+                        shape: HitmapShape::Segment([*last_point, *point], thickness / 2.0),
+                    });
+                }
+
+                last_point = Some(point);
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn insert_circle(&self, center: &GeometryPoint, radius: ConrodScalar) {
+        if let Some(series) = self.series.get() {
+            let mut index = self.index.borrow_mut();
+            let primitive = index.next_primitive(series);
+
+            index.insert(HitmapEntry {
+                series,
+                primitive,
+                vertex: 0,
+                shape: HitmapShape::Circle(*center, radius),
+            });
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn insert_rect(&self, upper_left: &GeometryPoint, bottom_right: &GeometryPoint) {
+        if let Some(series) = self.series.get() {
+            let mut index = self.index.borrow_mut();
+            let primitive = index.next_primitive(series);
+
+            index.insert(HitmapEntry {
+                series,
+                primitive,
+                vertex: 0,
+                shape: HitmapShape::Rect(*upper_left, *bottom_right),
+            });
+        }
+    }
+}
+
+impl HitmapIndex {
+    fn clear(&mut self, size: (u32, u32)) {
+        self.cells_max = (
+            (size.0 as ConrodScalar / HITMAP_CELL_SIZE) as i32,
+            (size.1 as ConrodScalar / HITMAP_CELL_SIZE) as i32,
+        );
+
+        self.entries.clear();
+        self.counters.clear();
+
+        // Notice: clear cell contents rather than cells themselves, as cells would likely be \
+        //   re-used on next frame.
+        for cell_entries in self.cells.values_mut() {
+            cell_entries.clear();
+        }
+    }
+
+    fn next_primitive(&mut self, series: u64) -> usize {
+        let counter = self.counters.entry(series).or_insert(0);
+        let primitive = *counter;
+
+        *counter += 1;
+
+        primitive
+    }
+
+    fn insert(&mut self, entry: HitmapEntry) {
+        let entry_index = self.entries.len();
+        let (bound_start, bound_end) = entry.shape.bounds();
+
+        let (cell_start, cell_end) = (self.cell_of(&bound_start), self.cell_of(&bound_end));
+
+        // Register entry in all cells that its bounding box covers
+        for cell_x in cell_start.0..=cell_end.0 {
+            for cell_y in cell_start.1..=cell_end.1 {
+                self.cells
+                    .entry((cell_x, cell_y))
+                    .or_default()
+                    .push(entry_index);
+            }
+        }
+
+        self.entries.push(entry);
+    }
+
+    #[inline(always)]
+    fn cell_of(&self, point: &GeometryPoint) -> HitmapCell {
+        // Notice: cells are clipped to the plot area, as primitives with extreme coordinates (eg. \
+        //   far outside of the chart range) would otherwise be registered in a huge number of \
+        //   cells. Primitives and positions outside of the plot area map to its border cells.
+        (
+            ((point[0] / HITMAP_CELL_SIZE).floor() as i32).clamp(0, self.cells_max.0),
+            ((point[1] / HITMAP_CELL_SIZE).floor() as i32).clamp(0, self.cells_max.1),
+        )
+    }
+}
+
+impl HitmapShape {
+    fn bounds(&self) -> (GeometryPoint, GeometryPoint) {
+        match self {
+            HitmapShape::Segment(points, margin) => (
+                [
+                    points[0][0].min(points[1][0]) - margin,
+                    points[0][1].min(points[1][1]) - margin,
+                ],
+                [
+                    points[0][0].max(points[1][0]) + margin,
+                    points[0][1].max(points[1][1]) + margin,
+                ],
+            ),
+            HitmapShape::Circle(center, radius) => (
+                [center[0] - radius, center[1] - radius],
+                [center[0] + radius, center[1] + radius],
+            ),
+            HitmapShape::Rect(upper_left, bottom_right) => (
+                [
+                    upper_left[0].min(bottom_right[0]),
+                    upper_left[1].min(bottom_right[1]),
+                ],
+                [
+                    upper_left[0].max(bottom_right[0]),
+                    upper_left[1].max(bottom_right[1]),
+                ],
+            ),
+        }
+    }
+
+    fn nearest(&self, point: &GeometryPoint) -> (GeometryPoint, ConrodScalar, usize) {
+        // Format: (nearest_position, distance, nearest_vertex_offset)
+        match self {
+            HitmapShape::Segment(points, margin) => {
+                // Project point on segment, and clamp projection to segment edges
+                let segment = [points[1][0] - points[0][0], points[1][1] - points[0][1]];
+                let length_squared = segment[0] * segment[0] + segment[1] * segment[1];
+
+                let t = if length_squared > 0.0 {
+                    (((point[0] - points[0][0]) * segment[0]
+                        + (point[1] - points[0][1]) * segment[1])
+                        / length_squared)
+                        .clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+
+                let projected = [points[0][0] + t * segment[0], points[0][1] + t * segment[1]];
+
+                (
+                    projected,
+                    (Self::distance(point, &projected) - margin).max(0.0),
+                    if t < 0.5 { 0 } else { 1 },
+                )
+            }
+            HitmapShape::Circle(center, radius) => (
+                *center,
+                (Self::distance(point, center) - radius).max(0.0),
+                0,
+            ),
+            HitmapShape::Rect(..) => {
+                // Clamp point within rectangle (distance is zero if the point is inside)
+                let (bound_start, bound_end) = self.bounds();
+
+                let clamped = [
+                    point[0].max(bound_start[0]).min(bound_end[0]),
+                    point[1].max(bound_start[1]).min(bound_end[1]),
+                ];
+
+                (clamped, Self::distance(point, &clamped), 0)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn distance(point: &GeometryPoint, other: &GeometryPoint) -> ConrodScalar {
+        ((point[0] - other[0]).powi(2) + (point[1] - other[1]).powi(2)).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTREME: ConrodScalar = i32::MAX as ConrodScalar;
+
+    fn hitmap() -> ConrodBackendHitmap {
+        let hitmap = ConrodBackendHitmap::new();
+
+        hitmap.prepare((800, 480));
+        hitmap.set_series(Some(1));
+
+        hitmap
+    }
+
+    #[test]
+    fn it_clips_cells_to_the_plot_area() {
+        let hitmap = hitmap();
+        let index = hitmap.index.borrow();
+
+        assert_eq!(index.cell_of(&[-EXTREME, -EXTREME]), (0, 0));
+        assert_eq!(index.cell_of(&[EXTREME, EXTREME]), (50, 30));
+        assert_eq!(index.cell_of(&[-1e300, 1e300]), (0, 30));
+        assert_eq!(index.cell_of(&[ConrodScalar::NAN, 100.0]), (0, 6));
+    }
+
+    #[test]
+    fn it_indexes_primitives_with_extreme_coordinates() {
+        let hitmap = hitmap();
+
+        hitmap.insert_rect(&[-EXTREME, -EXTREME], &[EXTREME, EXTREME]);
+        hitmap.insert_path([[-EXTREME, 10.0], [EXTREME, 10.0]].iter(), 1.0);
+
+        // Primitives only get registered in cells of the plot area
+        assert_eq!(
+            hitmap
+                .index
+                .borrow()
+                .cells
+                .values()
+                .filter(|cell_entries| !cell_entries.is_empty())
+                .count(),
+            51 * 31
+        );
+
+        let hit = hitmap.nearest((400, 10), 2.0).expect("primitives are hit");
+
+        assert_eq!((hit.series, hit.distance), (1, 0.0));
+    }
+
+    #[test]
+    fn it_finds_the_nearest_primitive_within_distance() {
+        let hitmap = hitmap();
+
+        // Path is 2 pixels thick, ie. its stroke is 1 pixel away from its center line
+        hitmap.insert_path([[100.0, 100.0], [200.0, 100.0]].iter(), 2.0);
+        hitmap.insert_circle(&[300.0, 100.0], 10.0);
+        hitmap.insert_rect(&[400.0, 100.0], &[450.0, 150.0]);
+
+        let hit = hitmap.nearest((150, 105), 5.0).expect("path is hit");
+
+        assert_eq!(
+            (hit.primitive, hit.position, hit.distance),
+            (0, (150.0, 100.0), 4.0)
+        );
+        assert_eq!(hitmap.nearest((150, 105), 3.0), None);
+
+        let hit = hitmap.nearest((300, 115), 5.0).expect("circle is hit");
+
+        assert_eq!((hit.primitive, hit.distance), (1, 5.0));
+
+        let hit = hitmap.nearest((420, 120), 0.0).expect("rect is hit");
+
+        assert_eq!(
+            (hit.primitive, hit.position, hit.distance),
+            (2, (420.0, 120.0), 0.0)
+        );
+
+        // Nearest primitive wins over farther ones (even across cells)
+        let hit = hitmap
+            .nearest((250, 100), 100.0)
+            .expect("circle is nearest");
+
+        assert_eq!((hit.primitive, hit.distance), (1, 40.0));
+        assert_eq!(hitmap.nearest((600, 400), 100.0), None);
+    }
+
+    #[test]
+    fn it_counts_primitives_per_series() {
+        let hitmap = hitmap();
+
+        hitmap.insert_circle(&[100.0, 100.0], 5.0);
+        hitmap.insert_circle(&[200.0, 100.0], 5.0);
+
+        hitmap.set_series(Some(2));
+        hitmap.insert_circle(&[300.0, 100.0], 5.0);
+
+        // Culled primitives still consume their index
+        hitmap.set_series(Some(1));
+        hitmap.skip_primitive();
+        hitmap.insert_circle(&[400.0, 100.0], 5.0);
+
+        let hits: Vec<_> = [100, 200, 300, 400]
+            .iter()
+            .map(|x| {
+                let hit = hitmap.nearest((*x, 100), 0.0).expect("circle is hit");
+
+                (hit.series, hit.primitive)
+            })
+            .collect();
+
+        assert_eq!(hits, vec![(1, 0), (1, 1), (2, 0), (1, 3)]);
+    }
+
+    #[test]
+    fn it_finds_the_nearest_path_vertex() {
+        let hitmap = hitmap();
+
+        hitmap.insert_path(
+            [[0.0, 0.0], [100.0, 0.0], [100.0, 100.0], [200.0, 100.0]].iter(),
+            1.0,
+        );
+
+        let vertex_at = |position| hitmap.nearest(position, 5.0).expect("path is hit").vertex;
+
+        assert_eq!(vertex_at((10, 1)), 0);
+        assert_eq!(vertex_at((90, 1)), 1);
+        assert_eq!(vertex_at((101, 40)), 1);
+        assert_eq!(vertex_at((101, 60)), 2);
+        assert_eq!(vertex_at((190, 101)), 3);
+    }
+
+    #[test]
+    fn it_returns_the_primitive_on_top_on_ties() {
+        let hitmap = hitmap();
+
+        // Overlapping rectangles, spanning multiple cells
+        hitmap.insert_rect(&[0.0, 0.0], &[200.0, 200.0]);
+        hitmap.set_series(Some(2));
+        hitmap.insert_rect(&[100.0, 100.0], &[300.0, 300.0]);
+        hitmap.set_series(Some(3));
+        hitmap.insert_rect(&[10.0, 10.0], &[20.0, 20.0]);
+
+        for (position, series) in [
+            ((150, 150), 2),
+            ((199, 101), 2),
+            ((50, 50), 1),
+            ((15, 15), 3),
+            ((250, 250), 2),
+        ] {
+            let hit = hitmap.nearest(position, 0.0).expect("rectangle is hit");
+
+            assert_eq!(hit.series, series, "position {:?}", position);
+        }
+
+        // Equidistant primitives also resolve to the one drawn last
+        hitmap.set_series(Some(4));
+        hitmap.insert_circle(&[500.0, 100.0], 10.0);
+        hitmap.set_series(Some(5));
+        hitmap.insert_circle(&[540.0, 100.0], 10.0);
+
+        assert_eq!(
+            hitmap.nearest((520, 100), 20.0).map(|hit| hit.series),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn it_does_not_index_primitives_without_series() {
+        let hitmap = hitmap();
+
+        hitmap.set_series(None);
+        hitmap.insert_circle(&[100.0, 100.0], 5.0);
+        hitmap.insert_path([[0.0, 200.0], [100.0, 200.0]].iter(), 1.0);
+        hitmap.insert_rect(&[200.0, 200.0], &[300.0, 300.0]);
+        hitmap.skip_primitive();
+
+        assert_eq!(hitmap.nearest((100, 100), 10.0), None);
+        assert_eq!(hitmap.nearest((50, 200), 10.0), None);
+        assert_eq!(hitmap.nearest((250, 250), 10.0), None);
+
+        // Unindexed primitives do not consume indices of the series
+        hitmap.set_series(Some(1));
+        hitmap.insert_circle(&[100.0, 100.0], 5.0);
+
+        assert_eq!(
+            hitmap.nearest((100, 100), 0.0).map(|hit| hit.primitive),
+            Some(0)
+        );
+
+        // Preparing for the next frame clears the index and the series
+        hitmap.prepare((800, 480));
+        hitmap.insert_circle(&[100.0, 100.0], 5.0);
+
+        assert_eq!(hitmap.nearest((100, 100), 10.0), None);
+    }
+}
@@ -0,0 +1,521 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use std::ops::Range;
+
+use conrod_core::{self as conrod, position::Scalar as ConrodScalar};
+
+const VIEWPORT_ZOOM_SPEED: ConrodScalar = 0.01;
+const VIEWPORT_ZOOM_SPAN_MINIMUM: ConrodScalar = 1e-9;
+
+/// The zoom and pan interaction state of a chart drawn with the Conrod backend
+///
+/// This consumes Conrod UI events targeting the parent canvas of the plot (ie. drag to pan, \
+///   scroll to zoom, double-click to reset), and maintains the data ranges that should be \
+///   passed to `ChartBuilder::build_cartesian_2d()` when building the chart.
+///
+/// **This should be put outside of your drawer loop and built once, as it holds the interaction state across frames.**
+pub struct ConrodBackendViewport {
+    initial: [Range<ConrodScalar>; 2],
+    current: [Range<ConrodScalar>; 2],
+    axes: [bool; 2],
+}
+
+impl ConrodBackendViewport {
+    /// Create a new viewport, with:
+    /// - `x`: the initial data range on the X axis (restored upon reset)
+    /// - `y`: the initial data range on the Y axis (restored upon reset)
+    pub fn new(x: Range<ConrodScalar>, y: Range<ConrodScalar>) -> Self {
+        Self {
+            initial: [x.clone(), y.clone()],
+            current: [x, y],
+            axes: [true, true],
+        }
+    }
+
+    /// Restrict the axes that can be zoomed and panned (eg. only the X axis for time-series charts)
+    pub fn with_axes(mut self, x: bool, y: bool) -> Self {
+        self.axes = [x, y];
+
+        self
+    }
+
+    /// Get the current data range on the X axis
+    pub fn x_range(&self) -> Range<ConrodScalar> {
+        self.current[0].clone()
+    }
+
+    /// Get the current data range on the Y axis
+    pub fn y_range(&self) -> Range<ConrodScalar> {
+        self.current[1].clone()
+    }
+
+    /// Reset the viewport to its initial data ranges
+    pub fn reset(&mut self) {
+        self.current = self.initial.clone();
+    }
+
+    /// Update the viewport from the UI events of the current frame, with:
+    /// - `ui`: the `Ui` (or `UiCell`) for this frame
+    /// - `ids.parent`: the `widget::Id` of the canvas that contains your plot
+    ///
+    /// Only the events of the parent canvas are handled (ie. a drag that started on another \
+    ///   widget overlapping the canvas does not pan the chart). Plot widgets are set as graphics \
+    ///   for the parent canvas, so that events over them are delivered to the canvas.
+    ///
+    /// Returns whether the viewport was changed.
+    pub fn update(&mut self, ui: &conrod::Ui, parent: conrod::widget::Id) -> bool {
+        let mut changed = false;
+
+        if let Some(parent_rect) = ui.rect_of(parent) {
+            let input = ui.widget_input(parent);
+
+            // Notice: widget events are relative to the center of the widget, hence why the \
+            //   parent rectangle is centered on the origin there.
+            let (parent_rect, mouse_xy) = (
+                conrod::Rect::from_xy_dim([0.0, 0.0], parent_rect.dim()),
+                input.mouse().map(|mouse| mouse.rel_xy()),
+            );
+
+            for event in input.events() {
+                changed |= self.handle_event(&event, &parent_rect, mouse_xy);
+            }
+        }
+
+        changed
+    }
+
+    /// Update the viewport from a single widget event of the parent canvas, with:
+    /// - `event`: the Conrod widget event to handle (as yielded by `ui.widget_input(parent).events()`)
+    /// - `parent_rect`: the rectangle of the canvas that contains your plot, relative to its center (as widget events are)
+    /// - `mouse_xy`: the position of the mouse relative to the center of the canvas, if captured by the canvas (used to anchor zoom)
+    ///
+    /// Returns whether the viewport was changed.
+    pub fn handle_event(
+        &mut self,
+        event: &conrod::event::Widget,
+        parent_rect: &conrod::Rect,
+        mouse_xy: Option<conrod::position::Point>,
+    ) -> bool {
+        match event {
+            conrod::event::Widget::Drag(drag)
+                if drag.button == conrod::input::MouseButton::Left
+                    && parent_rect.is_over(drag.origin) =>
+            {
+                self.pan(parent_rect, drag.delta_xy)
+            }
+            conrod::event::Widget::Scroll(scroll) => match mouse_xy {
+                Some(mouse_xy) if parent_rect.is_over(mouse_xy) => self.zoom(
+                    parent_rect,
+                    mouse_xy,
+                    (scroll.y * VIEWPORT_ZOOM_SPEED).exp(),
+                ),
+                _ => false,
+            },
+            conrod::event::Widget::DoubleClick(double_click)
+                if double_click.button == conrod::input::MouseButton::Left
+                    && parent_rect.is_over(double_click.xy) =>
+            {
+                let changed = self.current != self.initial;
+
+                self.reset();
+
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn pan(&mut self, parent_rect: &conrod::Rect, delta_xy: conrod::position::Point) -> bool {
+        let mut changed = false;
+
+        for (axis, (delta, length)) in [
+            (delta_xy[0], parent_rect.w()),
+            (delta_xy[1], parent_rect.h()),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if self.axes[axis] && *delta != 0.0 && *length > 0.0 {
+                let range = &mut self.current[axis];
+
+                // Move the data range in the opposite direction of the drag (Conrod Y axis points \
+                //   upwards, as data Y axis does)
+                let shift = -delta * (range.end - range.start) / length;
+
+                range.start += shift;
+                range.end += shift;
+
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    fn zoom(
+        &mut self,
+        parent_rect: &conrod::Rect,
+        mouse_xy: conrod::position::Point,
+        factor: ConrodScalar,
+    ) -> bool {
+        let mut changed = false;
+
+        for (axis, (position, start, length)) in [
+            (mouse_xy[0], parent_rect.x.start, parent_rect.w()),
+            (mouse_xy[1], parent_rect.y.start, parent_rect.h()),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if self.axes[axis] && factor != 1.0 && *length > 0.0 {
+                let range = &mut self.current[axis];
+
+                // Zoom around the data value under the mouse, so that it stays in place
+                let anchor = range.start + (position - start) / length * (range.end - range.start);
+
+                let (new_start, new_end) = (
+                    anchor + (range.start - anchor) * factor,
+                    anchor + (range.end - anchor) * factor,
+                );
+
+                // Prevent zooming in infinitely (this would collapse the data range)
+                if (new_end - new_start).abs() > VIEWPORT_ZOOM_SPAN_MINIMUM {
+                    range.start = new_start;
+                    range.end = new_end;
+
+                    changed = true;
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use conrod::input::{keyboard::ModifierKey, MouseButton};
+
+    const EPSILON: ConrodScalar = 1e-9;
+
+    fn parent_rect() -> conrod::Rect {
+        // 200x100 canvas, relative to its center (as widget events are)
+        conrod::Rect::from_xy_dim([0.0, 0.0], [200.0, 100.0])
+    }
+
+    fn viewport() -> ConrodBackendViewport {
+        ConrodBackendViewport::new(0.0..10.0, 0.0..100.0)
+    }
+
+    fn drag(
+        button: MouseButton,
+        origin: conrod::position::Point,
+        delta_xy: conrod::position::Point,
+    ) -> conrod::event::Widget {
+        conrod::event::Widget::Drag(conrod::event::Drag {
+            button,
+            origin,
+            from: origin,
+            to: [origin[0] + delta_xy[0], origin[1] + delta_xy[1]],
+            delta_xy,
+            total_delta_xy: delta_xy,
+            modifiers: ModifierKey::NO_MODIFIER,
+        })
+    }
+
+    fn scroll(y: ConrodScalar) -> conrod::event::Widget {
+        conrod::event::Widget::Scroll(conrod::event::Scroll {
+            x: 0.0,
+            y,
+            modifiers: ModifierKey::NO_MODIFIER,
+        })
+    }
+
+    fn double_click(xy: conrod::position::Point) -> conrod::event::Widget {
+        conrod::event::Widget::DoubleClick(conrod::event::DoubleClick {
+            button: MouseButton::Left,
+            xy,
+            modifiers: ModifierKey::NO_MODIFIER,
+        })
+    }
+
+    fn value_at(
+        range: &Range<ConrodScalar>,
+        position: ConrodScalar,
+        start: ConrodScalar,
+        length: ConrodScalar,
+    ) -> ConrodScalar {
+        range.start + (position - start) / length * (range.end - range.start)
+    }
+
+    fn assert_range_eq(range: Range<ConrodScalar>, expected: Range<ConrodScalar>) {
+        assert!(
+            (range.start - expected.start).abs() < EPSILON
+                && (range.end - expected.end).abs() < EPSILON,
+            "range {:?} is not {:?}",
+            range,
+            expected
+        );
+    }
+
+    #[test]
+    fn it_pans_against_the_drag_direction() {
+        let (mut viewport, rect) = (viewport(), parent_rect());
+
+        // Dragging right and up moves the data ranges left and down (content follows the mouse)
+        assert!(viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert_range_eq(viewport.x_range(), -1.0..9.0);
+        assert_range_eq(viewport.y_range(), -10.0..90.0);
+
+        // Dragging left and down moves them back
+        assert!(viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [-20.0, -10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert_range_eq(viewport.x_range(), 0.0..10.0);
+        assert_range_eq(viewport.y_range(), 0.0..100.0);
+    }
+
+    #[test]
+    fn it_ignores_drags_from_outside_or_with_other_buttons() {
+        let (mut viewport, rect) = (viewport(), parent_rect());
+
+        assert!(!viewport.handle_event(
+            &drag(MouseButton::Left, [150.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert!(!viewport.handle_event(
+            &drag(MouseButton::Right, [0.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert!(!viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [0.0, 0.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+
+        assert_range_eq(viewport.x_range(), 0.0..10.0);
+        assert_range_eq(viewport.y_range(), 0.0..100.0);
+    }
+
+    #[test]
+    fn it_zooms_around_the_value_under_the_mouse() {
+        let (mut viewport, rect) = (viewport(), parent_rect());
+
+        for (mouse_xy, amount) in [
+            ([50.0, 25.0], 100.0),
+            ([50.0, 25.0], -250.0),
+            ([-90.0, -40.0], -50.0),
+            ([0.0, 0.0], 30.0),
+        ] {
+            let (x_before, y_before) = (
+                value_at(&viewport.x_range(), mouse_xy[0], rect.x.start, rect.w()),
+                value_at(&viewport.y_range(), mouse_xy[1], rect.y.start, rect.h()),
+            );
+            let (x_span, y_span) = (
+                viewport.x_range().end - viewport.x_range().start,
+                viewport.y_range().end - viewport.y_range().start,
+            );
+
+            assert!(viewport.handle_event(&scroll(amount), &rect, Some(mouse_xy)));
+
+            let (x_after, y_after) = (
+                value_at(&viewport.x_range(), mouse_xy[0], rect.x.start, rect.w()),
+                value_at(&viewport.y_range(), mouse_xy[1], rect.y.start, rect.h()),
+            );
+
+            assert!((x_after - x_before).abs() < EPSILON && (y_after - y_before).abs() < EPSILON);
+
+            // Scrolling up zooms out (ie. spans grow), scrolling down zooms in
+            let factor = (amount * VIEWPORT_ZOOM_SPEED).exp();
+
+            assert!(
+                ((viewport.x_range().end - viewport.x_range().start) / x_span - factor).abs()
+                    < EPSILON
+            );
+            assert!(
+                ((viewport.y_range().end - viewport.y_range().start) / y_span - factor).abs()
+                    < EPSILON
+            );
+        }
+
+        // Scrolling with the mouse outside of (or not captured by) the canvas does nothing
+        let (x_range, y_range) = (viewport.x_range(), viewport.y_range());
+
+        assert!(!viewport.handle_event(&scroll(100.0), &rect, Some([150.0, 0.0])));
+        assert!(!viewport.handle_event(&scroll(100.0), &rect, None));
+        assert_eq!((viewport.x_range(), viewport.y_range()), (x_range, y_range));
+    }
+
+    #[test]
+    fn it_clamps_zoom_to_the_minimum_span() {
+        let (mut viewport, rect) = (viewport(), parent_rect());
+
+        // A single huge zoom-in would collapse the ranges, so it gets ignored
+        assert!(!viewport.handle_event(&scroll(-10_000.0), &rect, Some([50.0, 25.0])));
+        assert_range_eq(viewport.x_range(), 0.0..10.0);
+        assert_range_eq(viewport.y_range(), 0.0..100.0);
+
+        // Repeated zoom-ins stop right before spans reach the minimum (ie. within a single zoom \
+        //   step from it)
+        for _ in 0..1_000 {
+            viewport.handle_event(&scroll(-100.0), &rect, Some([50.0, 25.0]));
+        }
+
+        for range in [viewport.x_range(), viewport.y_range()] {
+            let span = range.end - range.start;
+
+            assert!(
+                span > VIEWPORT_ZOOM_SPAN_MINIMUM
+                    && span < VIEWPORT_ZOOM_SPAN_MINIMUM * (100.0 * VIEWPORT_ZOOM_SPEED).exp()
+            );
+        }
+
+        assert!(!viewport.handle_event(&scroll(-100.0), &rect, Some([50.0, 25.0])));
+    }
+
+    #[test]
+    fn it_masks_disabled_axes() {
+        let rect = parent_rect();
+
+        let mut viewport = viewport().with_axes(true, false);
+
+        assert!(viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert!(viewport.handle_event(&scroll(100.0), &rect, Some([50.0, 25.0])));
+        assert_range_eq(viewport.y_range(), 0.0..100.0);
+
+        let mut viewport = self::viewport().with_axes(false, true);
+
+        assert!(viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert!(viewport.handle_event(&scroll(100.0), &rect, Some([50.0, 25.0])));
+        assert_range_eq(viewport.x_range(), 0.0..10.0);
+
+        // Vertical drags do not change anything when only the X axis is enabled
+        let mut viewport = self::viewport().with_axes(true, false);
+
+        assert!(!viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [0.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+
+        let mut viewport = self::viewport().with_axes(false, false);
+
+        assert!(!viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0])
+        ));
+        assert!(!viewport.handle_event(&scroll(100.0), &rect, Some([50.0, 25.0])));
+    }
+
+    #[test]
+    fn it_resets_on_double_click() {
+        let (mut viewport, rect) = (viewport(), parent_rect());
+
+        // Nothing to reset yet
+        assert!(!viewport.handle_event(&double_click([0.0, 0.0]), &rect, Some([0.0, 0.0])));
+
+        viewport.handle_event(
+            &drag(MouseButton::Left, [0.0, 0.0], [20.0, 10.0]),
+            &rect,
+            Some([0.0, 0.0]),
+        );
+        viewport.handle_event(&scroll(100.0), &rect, Some([50.0, 25.0]));
+
+        // Double-clicking outside of the canvas does not reset
+        assert!(!viewport.handle_event(&double_click([150.0, 0.0]), &rect, Some([150.0, 0.0])));
+        assert!(viewport.x_range() != (0.0..10.0));
+
+        assert!(viewport.handle_event(&double_click([0.0, 0.0]), &rect, Some([0.0, 0.0])));
+        assert_eq!(
+            (viewport.x_range(), viewport.y_range()),
+            (0.0..10.0, 0.0..100.0)
+        );
+    }
+
+    #[test]
+    fn it_only_pans_from_drags_started_on_the_parent() {
+        use conrod::{event::Input, input, widget_ids, Positionable, Sizeable, Widget};
+
+        widget_ids!(struct Ids { parent, plot, overlapping });
+
+        let mut ui = conrod::UiBuilder::new([400.0, 300.0]).build();
+        let ids = Ids::new(ui.widget_id_generator());
+
+        // Draw the parent canvas with a plot widget on it (set as a graphic of the canvas, as \
+        //   the backend does), and another widget overlapping its left part
+        let frame = |ui: &mut conrod::Ui, viewport: &mut ConrodBackendViewport| {
+            let ui = &mut ui.set_widgets();
+
+            conrod::widget::Canvas::new()
+                .w_h(200.0, 100.0)
+                .middle()
+                .set(ids.parent, ui);
+            conrod::widget::Rectangle::fill([200.0, 100.0])
+                .middle_of(ids.parent)
+                .graphics_for(ids.parent)
+                .set(ids.plot, ui);
+            conrod::widget::Rectangle::fill([50.0, 100.0])
+                .mid_left_of(ids.parent)
+                .parent(ui.window)
+                .set(ids.overlapping, ui);
+
+            viewport.update(ui, ids.parent)
+        };
+
+        let drag = |ui: &mut conrod::Ui, from: conrod::position::Point| {
+            for event in [
+                Input::Motion(input::Motion::MouseCursor {
+                    x: from[0],
+                    y: from[1],
+                }),
+                Input::Press(input::Button::Mouse(MouseButton::Left)),
+                Input::Motion(input::Motion::MouseCursor {
+                    x: from[0] + 20.0,
+                    y: from[1],
+                }),
+                Input::Release(input::Button::Mouse(MouseButton::Left)),
+            ] {
+                ui.handle_event(event);
+            }
+        };
+
+        let mut viewport = viewport();
+
+        frame(&mut ui, &mut viewport);
+
+        // Drag starting on the overlapping widget
+        drag(&mut ui, [-90.0, 0.0]);
+
+        assert!(!frame(&mut ui, &mut viewport));
+        assert_range_eq(viewport.x_range(), 0.0..10.0);
+
+        // Drag starting on the plot (over the parent canvas)
+        drag(&mut ui, [0.0, 0.0]);
+
+        assert!(frame(&mut ui, &mut viewport));
+        assert_range_eq(viewport.x_range(), -1.0..9.0);
+    }
+}
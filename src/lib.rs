@@ -16,10 +16,24 @@
 
 mod backend;
 mod error;
+mod geometry;
 mod graph;
+mod hitmap;
+mod interaction;
+mod report;
+mod svg;
+mod transform;
 mod triangulate;
 mod utils;
 
 pub use backend::ConrodBackend;
-pub use error::ConrodBackendError;
+pub use error::{ConrodBackendError, ConrodBackendErrorContext, ConrodBackendPrimitive};
+pub use geometry::{ConrodBackendGeometry, ConrodBackendGeometryBuilder};
 pub use graph::ConrodBackendReusableGraph;
+pub use hitmap::{ConrodBackendHit, ConrodBackendHitmap};
+pub use interaction::ConrodBackendViewport;
+pub use report::{
+    ConrodBackendCullReport, ConrodBackendDegradation, ConrodBackendRenderReport,
+    ConrodBackendSkipReport,
+};
+pub use transform::ConrodBackendTransform;
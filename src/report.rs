@@ -0,0 +1,172 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use std::time::{Duration, Instant};
+
+use crate::error::ConrodBackendPrimitive;
+
+/// The report of all operations that were skipped while drawing a frame, as they are not supported by the Conrod backend
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConrodBackendSkipReport {
+    /// The number of pixels that were not drawn (from `draw_pixel()`)
+    pub pixels: usize,
+    /// The number of bitmaps that were not drawn (from `blit_bitmap()`)
+    pub bitmaps: usize,
+    /// The number of texts that were drawn without their transform (eg. rotated texts)
+    pub text_transforms: usize,
+}
+
+impl ConrodBackendSkipReport {
+    /// Check whether no operation was skipped
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Get the total number of skipped operations
+    pub fn total(&self) -> usize {
+        self.pixels + self.bitmaps + self.text_transforms
+    }
+
+    #[inline(always)]
+    pub(crate) fn count(&mut self, primitive: ConrodBackendPrimitive) {
+        match primitive {
+            ConrodBackendPrimitive::Pixel => self.pixels += 1,
+            ConrodBackendPrimitive::Bitmap => self.bitmaps += 1,
+            ConrodBackendPrimitive::Text => self.text_transforms += 1,
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.pixels += other.pixels;
+        self.bitmaps += other.bitmaps;
+        self.text_transforms += other.text_transforms;
+    }
+
+    #[inline(always)]
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The report of all primitives that were culled while drawing a frame, as they would not be visible
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConrodBackendCullReport {
+    /// The number of primitives that were not drawn as they are fully transparent (eg. invisible backgrounds)
+    pub transparent: usize,
+    /// The number of primitives that were not drawn as they are entirely outside of the plot area
+    pub off_canvas: usize,
+    /// The number of primitives that were not drawn as they do not cover any pixel (eg. zero-height histogram bars)
+    pub empty: usize,
+}
+
+impl ConrodBackendCullReport {
+    /// Check whether no primitive was culled
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Get the total number of culled primitives
+    pub fn total(&self) -> usize {
+        self.transparent + self.off_canvas + self.empty
+    }
+
+    #[inline(always)]
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The report of all primitives that were rendered while drawing a frame, along with their rendering cost
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConrodBackendRenderReport {
+    /// The number of lines that were drawn (from `draw_line()`)
+    pub lines: usize,
+    /// The number of rectangles that were drawn (from `draw_rect()`)
+    pub rects: usize,
+    /// The number of paths that were drawn (from `draw_path()`)
+    pub paths: usize,
+    /// The number of circles that were drawn (from `draw_circle()`)
+    pub circles: usize,
+    /// The number of polygons that were drawn (from `fill_polygon()`)
+    pub polygons: usize,
+    /// The number of texts that were drawn (from `draw_text()`)
+    pub texts: usize,
+    /// The number of Conrod widgets that were set
+    pub widgets: usize,
+    /// The number of triangles that were produced (from filled polygons, and batched lines and rectangles)
+    pub triangles: usize,
+    /// The number of path and polygon points that were received, before simplification
+    pub points_received: usize,
+    /// The number of path and polygon points that were kept, after simplification
+    pub points_simplified: usize,
+    /// The number of primitives that were culled (see `ConrodBackendCullReport` for details)
+    pub culled: usize,
+    /// The time spent triangulating polygons
+    pub triangulation_time: Duration,
+    /// The time spent instantiating Conrod widgets
+    pub widgets_time: Duration,
+    /// The highest degradation stage that was reached, as the frame time budget was exceeded
+    pub degradation: ConrodBackendDegradation,
+    /// The number of primitives that were rendered in a degraded way
+    pub degraded: usize,
+}
+
+/// The degradation stages applied once the frame time budget is exceeded (each stage also applies all previous stages)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConrodBackendDegradation {
+    /// The frame was drawn within its budget (or no budget was set)
+    #[default]
+    None,
+    /// Paths are decimated down to their envelope in each pixel column
+    Decimate,
+    /// Sub-pixel smoothing is dropped (ie. paths get simplified with a 1 pixel tolerance, and circles get rendered with fewer segments), as Conrod does not allow disabling anti-aliasing
+    Coarse,
+    /// Polygons are stroked as outlines, instead of being triangulated and filled
+    Outline,
+}
+
+impl ConrodBackendRenderReport {
+    /// Get the total number of drawn primitives
+    pub fn primitives(&self) -> usize {
+        self.lines + self.rects + self.paths + self.circles + self.polygons + self.texts
+    }
+
+    /// Check whether the frame got degraded, as its time budget was exceeded
+    pub fn is_degraded(&self) -> bool {
+        self.degradation != ConrodBackendDegradation::None
+    }
+
+    #[inline(always)]
+    pub(crate) fn count(&mut self, primitive: ConrodBackendPrimitive) {
+        match primitive {
+            ConrodBackendPrimitive::Line => self.lines += 1,
+            ConrodBackendPrimitive::Rect => self.rects += 1,
+            ConrodBackendPrimitive::Path => self.paths += 1,
+            ConrodBackendPrimitive::Circle => self.circles += 1,
+            ConrodBackendPrimitive::Polygon => self.polygons += 1,
+            ConrodBackendPrimitive::Text => self.texts += 1,
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn count_widget(&mut self, started: Instant) {
+        self.widgets += 1;
+        self.widgets_time += started.elapsed();
+    }
+
+    #[inline(always)]
+    pub(crate) fn count_degradation(&mut self, degradation: ConrodBackendDegradation) {
+        self.degradation = self.degradation.max(degradation);
+    }
+
+    #[inline(always)]
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,429 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use std::fmt::Write;
+
+use conrod_core as conrod;
+use plotters_backend::FontStyle;
+
+use crate::geometry::{self, ConrodBackendGeometry, GeometryPrimitive};
+use crate::utils::{color, convert};
+
+impl ConrodBackendGeometry {
+    /// Export the geometry as a SVG document
+    ///
+    /// The SVG document is built from the exact same primitives that are sent to Conrod, which \
+    ///   means that the export matches the on-screen rendering (fonts are referenced by family \
+    ///   name, as they are not embedded in the document).
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+
+        // Notice: writing to a string never fails, hence why results are ignored there.
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            self.size.0, self.size.1, self.size.0, self.size.1
+        );
+
+        for primitive in self.primitives.iter() {
+            let _ = match primitive {
+                GeometryPrimitive::Line {
+                    from,
+                    to,
+                    color,
+                    thickness,
+                } => {
+                    // Export lines as the same quads that are rendered with Conrod
+                    if let Some(quad) = geometry::line_quad(from, to, *thickness) {
+                        writeln!(
+                            svg,
+                            r#"<polygon points="{},{} {},{} {},{} {},{}" {}/>"#,
+                            quad[0][0],
+                            quad[0][1],
+                            quad[1][0],
+                            quad[1][1],
+                            quad[2][0],
+                            quad[2][1],
+                            quad[3][0],
+                            quad[3][1],
+                            SvgPaint::fill(color),
+                        )
+                    } else {
+                        Ok(())
+                    }
+                }
+                GeometryPrimitive::Rect {
+                    upper_left,
+                    size,
+                    color,
+                    thickness,
+                    fill,
+                } => {
+                    if *fill {
+                        writeln!(
+                            svg,
+                            r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                            upper_left[0],
+                            upper_left[1],
+                            size[0],
+                            size[1],
+                            SvgPaint::fill(color),
+                        )
+                    } else {
+                        // Export outlines as the same frame of filled rectangles that is rendered \
+                        //   with Conrod (this also covers rectangles without any width or height, \
+                        //   which SVG would not render as outlined rectangles)
+                        for (side_upper_left, side_size) in
+                            geometry::rect_outline(upper_left, size, *thickness)
+                                .iter()
+                                .flatten()
+                        {
+                            let (left, top, right, bottom) = (
+                                side_upper_left[0],
+                                side_upper_left[1],
+                                side_upper_left[0] + side_size[0],
+                                side_upper_left[1] + side_size[1],
+                            );
+
+                            let _ = writeln!(
+                                svg,
+                                r#"<polygon points="{},{} {},{} {},{} {},{}" {}/>"#,
+                                left,
+                                top,
+                                right,
+                                top,
+                                right,
+                                bottom,
+                                left,
+                                bottom,
+                                SvgPaint::fill(color),
+                            );
+                        }
+
+                        Ok(())
+                    }
+                }
+                GeometryPrimitive::Path {
+                    points,
+                    color,
+                    thickness,
+                } => {
+                    // Closed paths are stroked as rings (with a join on their closing vertex), \
+                    //   and all paths are aligned on pixel centers, as they are rendered with Conrod
+                    let (element, points) = if geometry::path_is_closed(points) {
+                        ("polygon", &points[..(points.len() - 1)])
+                    } else {
+                        ("polyline", &points[..])
+                    };
+
+                    let _ = write!(svg, r#"<{} points=""#, element);
+
+                    for point in geometry::stroke_points(points, *thickness) {
+                        let _ = write!(svg, "{},{} ", point[0], point[1]);
+                    }
+
+                    writeln!(svg, r#"" {}/>"#, SvgPaint::stroke(color, *thickness))
+                }
+                GeometryPrimitive::Circle {
+                    center,
+                    radius,
+                    color,
+                    thickness,
+                    fill,
+                    resolution: None,
+                } => writeln!(
+                    svg,
+                    r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                    center[0],
+                    center[1],
+                    radius,
+                    SvgPaint::shape(color, *thickness, *fill),
+                ),
+                GeometryPrimitive::Circle {
+                    center,
+                    radius,
+                    color,
+                    thickness,
+                    fill,
+                    resolution: Some(resolution),
+                } => {
+                    // Export coarse circles as the same polygon that is rendered with Conrod \
+                    //   (which places its points at regular angles, starting from the X axis)
+                    let _ = write!(svg, r#"<polygon points=""#);
+
+                    for index in 0..*resolution {
+                        let angle = 2.0 * std::f64::consts::PI * index as f64 / *resolution as f64;
+
+                        let _ = write!(
+                            svg,
+                            "{},{} ",
+                            center[0] + radius * angle.cos(),
+                            center[1] + radius * angle.sin()
+                        );
+                    }
+
+                    writeln!(svg, r#"" {}/>"#, SvgPaint::shape(color, *thickness, *fill))
+                }
+                GeometryPrimitive::Fill { triangles, color } => {
+                    // Merge all triangles into a single path (they never overlap)
+                    let _ = write!(svg, r#"<path d=""#);
+
+                    for triangle in triangles.iter() {
+                        let _ = write!(
+                            svg,
+                            "M{},{} L{},{} L{},{} Z ",
+                            triangle[0][0],
+                            triangle[0][1],
+                            triangle[1][0],
+                            triangle[1][1],
+                            triangle[2][0],
+                            triangle[2][1]
+                        );
+                    }
+
+                    writeln!(svg, r#"" {}/>"#, SvgPaint::fill(color))
+                }
+                GeometryPrimitive::Text {
+                    text,
+                    pos,
+                    size,
+                    color,
+                    family,
+                    style,
+                    ..
+                } => {
+                    // Apply the same text box placement as the one used for Conrod text widgets
+                    let (text_width_estimated, font_size_final) = convert::font_style(text, *size);
+
+                    writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-family="{}" font-size="{}"{} dominant-baseline="text-before-edge" {}>{}</text>"#,
+                        pos[0] - text_width_estimated,
+                        pos[1] - (size / 2.0 + 1.0),
+                        escape(family),
+                        font_size_final,
+                        match style {
+                            FontStyle::Normal => "",
+                            FontStyle::Bold => r#" font-weight="bold""#,
+                            FontStyle::Italic => r#" font-style="italic""#,
+                            FontStyle::Oblique => r#" font-style="oblique""#,
+                        },
+                        SvgPaint::fill(color),
+                        escape(text)
+                    )
+                }
+            };
+        }
+
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+enum SvgPaint<'a> {
+    Fill(&'a conrod::color::Color),
+    Stroke(&'a conrod::color::Color, conrod::position::Scalar),
+}
+
+impl<'a> SvgPaint<'a> {
+    #[inline(always)]
+    fn fill(color: &'a conrod::color::Color) -> Self {
+        SvgPaint::Fill(color)
+    }
+
+    #[inline(always)]
+    fn stroke(color: &'a conrod::color::Color, thickness: conrod::position::Scalar) -> Self {
+        SvgPaint::Stroke(color, thickness)
+    }
+
+    #[inline(always)]
+    fn shape(
+        color: &'a conrod::color::Color,
+        thickness: conrod::position::Scalar,
+        fill: bool,
+    ) -> Self {
+        if fill {
+            Self::fill(color)
+        } else {
+            Self::stroke(color, thickness)
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for SvgPaint<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SvgPaint::Fill(color) => {
+                let ((r, g, b), a) = color::Color::to_srgb_components(color);
+
+                write!(
+                    fmt,
+                    r#"fill="rgb({},{},{})" fill-opacity="{}" stroke="none""#,
+                    r, g, b, a
+                )
+            }
+            SvgPaint::Stroke(color, thickness) => {
+                let ((r, g, b), a) = color::Color::to_srgb_components(color);
+
+                write!(
+                    fmt,
+                    r#"fill="none" stroke="rgb({},{},{})" stroke-opacity="{}" stroke-width="{}""#,
+                    r, g, b, a, thickness
+                )
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::geometry::GeometryPoint;
+
+    const COLOR: conrod::color::Color = conrod::color::Color::Rgba(1.0, 0.0, 0.0, 1.0);
+
+    fn paint() -> String {
+        SvgPaint::fill(&COLOR).to_string()
+    }
+
+    fn svg_of(primitives: Vec<GeometryPrimitive>) -> Vec<String> {
+        let geometry = ConrodBackendGeometry {
+            size: (100, 50),
+            primitives,
+            ..Default::default()
+        };
+
+        // Only keep the primitive elements (ie. strip the document root)
+        let svg = geometry.to_svg();
+        let lines: Vec<&str> = svg.lines().collect();
+
+        lines[1..(lines.len() - 1)]
+            .iter()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_escapes_texts_and_attributes() {
+        let svg = svg_of(vec![GeometryPrimitive::Text {
+            text: r#"<a & "b">'"#.to_owned(),
+            pos: [50.0, 25.0],
+            size: 10.0,
+            color: COLOR,
+            justify: conrod::text::Justify::Left,
+            family: r#"Sans "Serif" & <Mono>"#.to_owned(),
+            style: FontStyle::Normal,
+        }]);
+
+        assert_eq!(svg.len(), 1);
+        assert!(svg[0].contains(r#" font-family="Sans &quot;Serif&quot; &amp; &lt;Mono&gt;" "#));
+        assert!(svg[0].ends_with(">&lt;a &amp; &quot;b&quot;&gt;&apos;</text>"));
+    }
+
+    #[test]
+    fn it_exports_rect_outlines_as_frame_quads() {
+        let svg = svg_of(vec![
+            GeometryPrimitive::Rect {
+                upper_left: [10.0, 10.0],
+                size: [20.0, 10.0],
+                color: COLOR,
+                thickness: 1.0,
+                fill: false,
+            },
+            GeometryPrimitive::Rect {
+                upper_left: [10.0, 10.0],
+                size: [0.0, 10.0],
+                color: COLOR,
+                thickness: 1.0,
+                fill: false,
+            },
+        ]);
+
+        // Outline strokes are centered on the centers of the corner pixels (as in Bitmap), and \
+        //   zero-width rectangles are exported as a single quad
+        assert_eq!(
+            svg,
+            vec![
+                format!(r#"<polygon points="10,10 31,10 31,11 10,11" {}/>"#, paint()),
+                format!(r#"<polygon points="10,20 31,20 31,21 10,21" {}/>"#, paint()),
+                format!(r#"<polygon points="10,11 11,11 11,20 10,20" {}/>"#, paint()),
+                format!(r#"<polygon points="30,11 31,11 31,20 30,20" {}/>"#, paint()),
+                format!(r#"<polygon points="10,10 11,10 11,21 10,21" {}/>"#, paint()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_offsets_odd_width_paths() {
+        let path = |points: &[GeometryPoint], thickness| GeometryPrimitive::Path {
+            points: points.to_vec(),
+            color: COLOR,
+            thickness,
+        };
+
+        let (closed, open) = (
+            [[10.0, 10.0], [20.0, 10.0], [20.0, 20.0], [10.0, 10.0]],
+            [[10.0, 10.0], [20.0, 10.0], [20.0, 20.0]],
+        );
+
+        let svg = svg_of(vec![
+            path(&closed, 1.0),
+            path(&closed, 2.0),
+            path(&open, 1.0),
+            path(&open, 2.0),
+        ]);
+
+        assert!(svg[0].starts_with(r#"<polygon points="10.5,10.5 20.5,10.5 20.5,20.5 " "#));
+        assert!(svg[1].starts_with(r#"<polygon points="10,10 20,10 20,20 " "#));
+        assert!(svg[2].starts_with(r#"<polyline points="10.5,10.5 20.5,10.5 20.5,20.5 " "#));
+        assert!(svg[3].starts_with(r#"<polyline points="10,10 20,10 20,20 " "#));
+    }
+
+    #[test]
+    fn it_exports_circles_with_their_rendered_resolution() {
+        let circle = |resolution| GeometryPrimitive::Circle {
+            center: [50.0, 25.0],
+            radius: 10.0,
+            color: COLOR,
+            thickness: 1.0,
+            fill: true,
+            resolution,
+        };
+
+        let svg = svg_of(vec![circle(None), circle(Some(4))]);
+
+        assert_eq!(
+            svg[0],
+            format!(r#"<circle cx="50" cy="25" r="10" {}/>"#, paint())
+        );
+        assert_eq!(
+            svg[1],
+            format!(
+                r#"<polygon points="60,25 50,35 40,25 50,15 " {}/>"#,
+                paint()
+            )
+        );
+    }
+}
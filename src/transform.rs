@@ -0,0 +1,313 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use conrod_core::position::Scalar as ConrodScalar;
+use plotters_backend::BackendCoord;
+
+/// An affine transform applied to all plot coordinates (in backend pixels), before they get sent to Conrod (eg. to rotate or mirror a chart, on displays mounted in portrait orientation)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConrodBackendTransform {
+    matrix: [[ConrodScalar; 3]; 2],
+}
+
+impl ConrodBackendTransform {
+    /// Create a new affine transform from its matrix, with:
+    /// - `matrix`: the rows `[a, b, c]` and `[d, e, f]` of the matrix, which maps `(x, y)` to `(a * x + b * y + c, d * x + e * y + f)`
+    pub fn new(matrix: [[ConrodScalar; 3]; 2]) -> Self {
+        Self { matrix }
+    }
+
+    /// Create the identity transform (ie. which does not change coordinates)
+    pub fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]])
+    }
+
+    /// Create a transform that translates coordinates, with:
+    /// - `(tx, ty)`: the translation in pixels
+    pub fn translate(tx: ConrodScalar, ty: ConrodScalar) -> Self {
+        Self::new([[1.0, 0.0, tx], [0.0, 1.0, ty]])
+    }
+
+    /// Create a transform that scales coordinates from the origin, with:
+    /// - `(sx, sy)`: the scale factors (negative factors mirror coordinates)
+    pub fn scale(sx: ConrodScalar, sy: ConrodScalar) -> Self {
+        Self::new([[sx, 0.0, 0.0], [0.0, sy, 0.0]])
+    }
+
+    /// Create a transform that rotates coordinates around the origin, with:
+    /// - `radians`: the rotation angle, clockwise on screen (as the Y axis points downwards)
+    pub fn rotate(radians: ConrodScalar) -> Self {
+        let (sin, cos) = radians.sin_cos();
+
+        Self::new([[cos, -sin, 0.0], [sin, cos, 0.0]])
+    }
+
+    /// Create a transform that rotates a plot by quarter turns, with:
+    /// - `turns`: the number of quarter turns, clockwise on screen (eg. `1` for 90°, `-1` for 270°)
+    /// - `(plot_width, plot_height)`: the size of your plot in pixels (ie. the size passed to the backend)
+    ///
+    /// The rotated plot is moved back to the top-left corner of its parent, which means that the parent should be `plot_height` wide and `plot_width` high for odd turns.
+    pub fn rotate_quarters(turns: i32, size: (u32, u32)) -> Self {
+        let (width, height) = (size.0 as ConrodScalar, size.1 as ConrodScalar);
+
+        match turns.rem_euclid(4) {
+            1 => Self::new([[0.0, -1.0, height], [1.0, 0.0, 0.0]]),
+            2 => Self::new([[-1.0, 0.0, width], [0.0, -1.0, height]]),
+            3 => Self::new([[0.0, 1.0, 0.0], [-1.0, 0.0, width]]),
+            _ => Self::identity(),
+        }
+    }
+
+    /// Create a transform that mirrors a plot, with:
+    /// - `horizontal`: whether to mirror the plot horizontally (ie. left becomes right)
+    /// - `vertical`: whether to mirror the plot vertically (ie. top becomes bottom)
+    /// - `(plot_width, plot_height)`: the size of your plot in pixels (ie. the size passed to the backend)
+    pub fn mirror(horizontal: bool, vertical: bool, size: (u32, u32)) -> Self {
+        let (width, height) = (size.0 as ConrodScalar, size.1 as ConrodScalar);
+
+        Self::new([
+            if horizontal {
+                [-1.0, 0.0, width]
+            } else {
+                [1.0, 0.0, 0.0]
+            },
+            if vertical {
+                [0.0, -1.0, height]
+            } else {
+                [0.0, 1.0, 0.0]
+            },
+        ])
+    }
+
+    /// Chain another transform, which gets applied after this one
+    pub fn then(&self, other: &Self) -> Self {
+        let (m, o) = (&self.matrix, &other.matrix);
+
+        Self::new([
+            [
+                o[0][0] * m[0][0] + o[0][1] * m[1][0],
+                o[0][0] * m[0][1] + o[0][1] * m[1][1],
+                o[0][0] * m[0][2] + o[0][1] * m[1][2] + o[0][2],
+            ],
+            [
+                o[1][0] * m[0][0] + o[1][1] * m[1][0],
+                o[1][0] * m[0][1] + o[1][1] * m[1][1],
+                o[1][0] * m[0][2] + o[1][1] * m[1][2] + o[1][2],
+            ],
+        ])
+    }
+
+    /// Apply this transform to a point
+    pub fn apply(&self, point: [ConrodScalar; 2]) -> [ConrodScalar; 2] {
+        let m = &self.matrix;
+
+        [
+            m[0][0] * point[0] + m[0][1] * point[1] + m[0][2],
+            m[1][0] * point[0] + m[1][1] * point[1] + m[1][2],
+        ]
+    }
+
+    /// Map a transformed position back to backend coordinates (eg. the mouse position acquired from `ConrodBackend::mouse_position()`), or `None` if this transform cannot be inverted
+    pub fn invert(&self, coord: BackendCoord) -> Option<BackendCoord> {
+        let m = &self.matrix;
+
+        let determinant = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        // Notice: the position is taken at the center of its pixel, and the inverted position is \
+        //   rounded down to the pixel that contains it.
+        let (x, y) = (
+            coord.0 as ConrodScalar + 0.5 - m[0][2],
+            coord.1 as ConrodScalar + 0.5 - m[1][2],
+        );
+
+        Some((
+            ((m[1][1] * x - m[0][1] * y) / determinant).floor() as i32,
+            ((m[0][0] * y - m[1][0] * x) / determinant).floor() as i32,
+        ))
+    }
+
+    #[inline(always)]
+    pub(crate) fn extents(&self, radius: ConrodScalar) -> [ConrodScalar; 2] {
+        // Half-extents of a transformed circle (ie. of its bounding box, which is exact for \
+        //   rotations by quarter turns, mirrors and scales)
+        let m = &self.matrix;
+
+        [
+            radius * m[0][0].hypot(m[0][1]),
+            radius * m[1][0].hypot(m[1][1]),
+        ]
+    }
+}
+
+impl Default for ConrodBackendTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: (u32, u32) = (200, 100);
+
+    fn transforms() -> Vec<ConrodBackendTransform> {
+        vec![
+            ConrodBackendTransform::identity(),
+            ConrodBackendTransform::rotate_quarters(1, SIZE),
+            ConrodBackendTransform::rotate_quarters(2, SIZE),
+            ConrodBackendTransform::rotate_quarters(3, SIZE),
+            ConrodBackendTransform::mirror(true, false, SIZE),
+            ConrodBackendTransform::mirror(false, true, SIZE),
+            ConrodBackendTransform::mirror(true, true, SIZE),
+            ConrodBackendTransform::rotate_quarters(1, SIZE).then(&ConrodBackendTransform::mirror(
+                true,
+                false,
+                (SIZE.1, SIZE.0),
+            )),
+            ConrodBackendTransform::scale(2.0, 3.0)
+                .then(&ConrodBackendTransform::translate(15.0, -5.0)),
+        ]
+    }
+
+    #[test]
+    fn it_rotates_plots_by_quarter_turns() {
+        let corners = |transform: ConrodBackendTransform| {
+            [
+                transform.apply([0.0, 0.0]),
+                transform.apply([200.0, 0.0]),
+                transform.apply([200.0, 100.0]),
+            ]
+        };
+
+        // Rotated plots stay in the top-left corner of their parent (which is 100 wide and 200 \
+        //   high for odd turns)
+        assert_eq!(
+            corners(ConrodBackendTransform::rotate_quarters(1, SIZE)),
+            [[100.0, 0.0], [100.0, 200.0], [0.0, 200.0]]
+        );
+        assert_eq!(
+            corners(ConrodBackendTransform::rotate_quarters(2, SIZE)),
+            [[200.0, 100.0], [0.0, 100.0], [0.0, 0.0]]
+        );
+        assert_eq!(
+            corners(ConrodBackendTransform::rotate_quarters(3, SIZE)),
+            [[0.0, 200.0], [0.0, 0.0], [100.0, 0.0]]
+        );
+
+        // Turns wrap around
+        assert_eq!(
+            ConrodBackendTransform::rotate_quarters(-1, SIZE),
+            ConrodBackendTransform::rotate_quarters(3, SIZE)
+        );
+        assert_eq!(
+            ConrodBackendTransform::rotate_quarters(4, SIZE),
+            ConrodBackendTransform::identity()
+        );
+
+        // Two quarter turns make a half turn (the second one rotating the rotated plot)
+        assert_eq!(
+            ConrodBackendTransform::rotate_quarters(1, SIZE).then(
+                &ConrodBackendTransform::rotate_quarters(1, (SIZE.1, SIZE.0))
+            ),
+            ConrodBackendTransform::rotate_quarters(2, SIZE)
+        );
+    }
+
+    #[test]
+    fn it_mirrors_plots() {
+        let point = [10.0, 20.0];
+
+        assert_eq!(
+            ConrodBackendTransform::mirror(false, false, SIZE).apply(point),
+            point
+        );
+        assert_eq!(
+            ConrodBackendTransform::mirror(true, false, SIZE).apply(point),
+            [190.0, 20.0]
+        );
+        assert_eq!(
+            ConrodBackendTransform::mirror(false, true, SIZE).apply(point),
+            [10.0, 80.0]
+        );
+        assert_eq!(
+            ConrodBackendTransform::mirror(true, true, SIZE).apply(point),
+            ConrodBackendTransform::rotate_quarters(2, SIZE).apply(point)
+        );
+
+        // Mirroring twice restores the plot
+        let mirror = ConrodBackendTransform::mirror(true, true, SIZE);
+
+        assert_eq!(mirror.then(&mirror), ConrodBackendTransform::identity());
+    }
+
+    #[test]
+    fn it_chains_transforms_in_order() {
+        let (translate, scale) = (
+            ConrodBackendTransform::translate(10.0, 0.0),
+            ConrodBackendTransform::scale(2.0, 3.0),
+        );
+
+        assert_eq!(translate.then(&scale).apply([1.0, 1.0]), [22.0, 3.0]);
+        assert_eq!(scale.then(&translate).apply([1.0, 1.0]), [12.0, 3.0]);
+
+        for transform in transforms() {
+            for other in transforms() {
+                let point = [13.0, 7.0];
+
+                assert_eq!(
+                    transform.then(&other).apply(point),
+                    other.apply(transform.apply(point))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_inverts_transformed_coords() {
+        for transform in transforms() {
+            for coord in [(0, 0), (199, 0), (0, 99), (199, 99), (57, 31), (-3, 120)] {
+                // Transform the center of the pixel, then invert the pixel that contains it
+                let point =
+                    transform.apply([coord.0 as ConrodScalar + 0.5, coord.1 as ConrodScalar + 0.5]);
+
+                assert_eq!(
+                    transform.invert((point[0].floor() as i32, point[1].floor() as i32)),
+                    Some(coord),
+                    "{:?} does not invert {:?}",
+                    transform,
+                    coord
+                );
+            }
+        }
+
+        assert_eq!(ConrodBackendTransform::scale(0.0, 1.0).invert((1, 1)), None);
+    }
+
+    #[test]
+    fn it_computes_extents_of_rotated_and_mirrored_rects() {
+        for transform in transforms() {
+            // Bounding box of the transformed square around a circle
+            let corners = [[-4.0, -4.0], [4.0, -4.0], [4.0, 4.0], [-4.0, 4.0]]
+                .map(|corner| transform.apply(corner));
+
+            let extent = |axis: usize| {
+                let (min, max) = corners.iter().fold(
+                    (ConrodScalar::INFINITY, ConrodScalar::NEG_INFINITY),
+                    |(min, max), corner| (min.min(corner[axis]), max.max(corner[axis])),
+                );
+
+                (max - min) / 2.0
+            };
+
+            assert_eq!(transform.extents(4.0), [extent(0), extent(1)]);
+        }
+    }
+}
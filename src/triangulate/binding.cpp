@@ -5,6 +5,9 @@
 // License: MIT
 
 #include <vector>
+#include <string>
+#include <exception>
+#include <stdexcept>
 #include <poly2tri.h>
 
 #include <common/shapes.cc>
@@ -13,15 +16,63 @@
 #include <sweep/sweep.cc>
 #include <sweep/sweep_context.cc>
 
+// Notice: C++ exceptions must never unwind across the FFI boundary, as this is undefined
+//   behaviour. Hence, all bindings catch any exception and return a status code instead. The
+//   reason of the last failure is retained per-thread, and can be acquired from Rust.
+#define P2T_STATUS_OK 0
+#define P2T_STATUS_ERROR 1
+
+#define P2T_GUARD_BEGIN try {
+#define P2T_GUARD_END \
+    return P2T_STATUS_OK; \
+    } catch (const std::exception &error) { \
+        p2t_last_error = error.what(); \
+        return P2T_STATUS_ERROR; \
+    } catch (...) { \
+        p2t_last_error = "unknown exception"; \
+        return P2T_STATUS_ERROR; \
+    }
+
+static thread_local std::string p2t_last_error;
+
+// Notice: poly2tri throws upon repeated consecutive points while building polyline edges, which
+//   would leak the edges that were already built if done from the 'SweepContext' constructor.
+//   Hence, polylines are checked beforehand, so that nothing gets built for invalid polylines.
+static void p2t_check_polyline(const std::vector<p2t::Point *> &vec)
+{
+    if (vec.size() < 3) {
+        throw std::runtime_error("polyline has less than 3 points");
+    }
+
+    for (size_t i = 0; i < vec.size(); i++) {
+        const p2t::Point *p = vec[i];
+        const p2t::Point *q = vec[(i + 1) % vec.size()];
+
+        if (p->x == q->x && p->y == q->y) {
+            throw std::runtime_error("polyline has repeated points");
+        }
+    }
+}
 
 extern "C" {
-    void *p2t_polyline_new()
+    const char *p2t_get_last_error()
+    {
+        return p2t_last_error.c_str();
+    }
+
+    int p2t_polyline_new(void **polyline_out)
     {
-        return reinterpret_cast<void *>(new std::vector<p2t::Point *>);
+        P2T_GUARD_BEGIN
+
+        *polyline_out = reinterpret_cast<void *>(new std::vector<p2t::Point *>);
+
+        P2T_GUARD_END
     }
 
-    void p2t_polyline_free(void *polyline)
+    int p2t_polyline_free(void *polyline)
     {
+        P2T_GUARD_BEGIN
+
         std::vector<p2t::Point *> *vec =
             reinterpret_cast<std::vector<p2t::Point *> *>(polyline);
 
@@ -30,84 +81,154 @@ extern "C" {
         }
 
         delete vec;
+
+        P2T_GUARD_END
     }
 
-    void p2t_polyline_add_point(void *polyline, double x, double y)
+    int p2t_polyline_add_point(void *polyline, double x, double y)
     {
+        P2T_GUARD_BEGIN
+
         std::vector<p2t::Point *> *vec =
             reinterpret_cast<std::vector<p2t::Point *> *>(polyline);
 
-        vec->push_back(new p2t::Point(x, y));
+        p2t::Point *point = new p2t::Point(x, y);
+
+        try {
+            vec->push_back(point);
+        } catch (...) {
+            delete point;
+
+            throw;
+        }
+
+        P2T_GUARD_END
     }
 
-    void *p2t_cdt_new(void *polyline)
+    int p2t_cdt_new(void *polyline, void **cdt_out)
     {
+        P2T_GUARD_BEGIN
+
         std::vector<p2t::Point *> *vec =
             reinterpret_cast<std::vector<p2t::Point *> *>(polyline);
 
+        // Notice: the polyline is only consumed (ie. its points get owned by the CDT) if the CDT
+        //   could be created, otherwise it is left untouched so that its owner frees it.
+        p2t_check_polyline(*vec);
+
         p2t::CDT *cdt = new p2t::CDT(*vec);
 
         delete vec;
 
-        return reinterpret_cast<void *>(cdt);
+        *cdt_out = reinterpret_cast<void *>(cdt);
+
+        P2T_GUARD_END
     }
 
-    void p2t_cdt_free(void *cdt)
+    int p2t_cdt_add_hole(void *cdt, void *polyline)
     {
+        P2T_GUARD_BEGIN
+
+        p2t::CDT *c = reinterpret_cast<p2t::CDT *>(cdt);
+
+        std::vector<p2t::Point *> *vec =
+            reinterpret_cast<std::vector<p2t::Point *> *>(polyline);
+
+        // Notice: the polyline is only consumed (ie. its points get owned by the CDT) if the hole
+        //   could be added, otherwise it is left untouched so that its owner frees it.
+        p2t_check_polyline(*vec);
+
+        c->AddHole(*vec);
+
+        delete vec;
+
+        P2T_GUARD_END
+    }
+
+    int p2t_cdt_free(void *cdt)
+    {
+        P2T_GUARD_BEGIN
+
         p2t::CDT *c = reinterpret_cast<p2t::CDT *>(cdt);
 
         delete c;
+
+        P2T_GUARD_END
     }
 
-    void p2t_cdt_triangulate(void *cdt)
+    int p2t_cdt_triangulate(void *cdt)
     {
+        P2T_GUARD_BEGIN
+
         p2t::CDT *c = reinterpret_cast<p2t::CDT *>(cdt);
 
         c->Triangulate();
+
+        P2T_GUARD_END
     }
 
-    void *p2t_cdt_get_triangles(void *cdt)
+    int p2t_cdt_get_triangles(void *cdt, void **triangles_out)
     {
+        P2T_GUARD_BEGIN
+
         p2t::CDT *c = reinterpret_cast<p2t::CDT *>(cdt);
 
         auto vec = new std::vector<p2t::Triangle *>(c->GetTriangles());
 
-        return reinterpret_cast<void *>(vec);
+        *triangles_out = reinterpret_cast<void *>(vec);
+
+        P2T_GUARD_END
     }
 
-    size_t p2t_triangles_count(void *triangles)
+    int p2t_triangles_count(void *triangles, size_t *count_out)
     {
+        P2T_GUARD_BEGIN
+
         std::vector<p2t::Triangle *> *t =
             reinterpret_cast<std::vector<p2t::Triangle *> *>(triangles);
 
-        return t->size();
+        *count_out = t->size();
+
+        P2T_GUARD_END
     }
 
-    void p2t_triangles_free(void *triangles)
+    int p2t_triangles_free(void *triangles)
     {
+        P2T_GUARD_BEGIN
+
         std::vector<p2t::Triangle *> *t =
             reinterpret_cast<std::vector<p2t::Triangle *> *>(triangles);
 
         delete t;
+
+        P2T_GUARD_END
     }
 
-    const void *p2t_triangles_get_triangle(
-        void *triangles, size_t idx)
+    int p2t_triangles_get_triangle(
+        void *triangles, size_t idx, const void **triangle_out)
     {
+        P2T_GUARD_BEGIN
+
         std::vector<p2t::Triangle *> *t =
             reinterpret_cast<std::vector<p2t::Triangle *> *>(triangles);
 
-        return reinterpret_cast<void *>((*t)[idx]);
+        *triangle_out = reinterpret_cast<const void *>(t->at(idx));
+
+        P2T_GUARD_END
     }
 
-    void p2t_triangle_get_point(
+    int p2t_triangle_get_point(
         const void *triangle, size_t idx, double *x_out, double *y_out)
     {
+        P2T_GUARD_BEGIN
+
         p2t::Triangle *t = (p2t::Triangle *)triangle;
 
         const p2t::Point *point = t->GetPoint(idx);
 
         *x_out = point->x;
         *y_out = point->y;
+
+        P2T_GUARD_END
     }
 }
@@ -10,46 +10,78 @@
 
 extern crate libc;
 
-use libc::{c_void, size_t};
+use libc::{c_char, c_int, c_void, size_t};
+use std::ffi::CStr;
 use std::mem;
+use std::ptr;
 
 type Scalar = f64;
 
-extern "C" {
-    fn p2t_polyline_new() -> *mut c_void;
-    fn p2t_polyline_free(polygon: *mut c_void);
-    fn p2t_polyline_add_point(polygon: *mut c_void, x: Scalar, y: Scalar);
+const STATUS_OK: c_int = 0;
+
+// Notice: this is the epsilon used by poly2tri orientation tests (see 'common/utils.h'), thus \
+//   points closer than this to a line are considered as collinear by poly2tri itself.
+const CHECK_EPSILON: Scalar = 1e-12;
 
-    fn p2t_cdt_new(polygon: *mut c_void) -> *mut c_void;
-    fn p2t_cdt_free(cdt: *mut c_void);
-    fn p2t_cdt_triangulate(cdt: *mut c_void);
-    fn p2t_cdt_get_triangles(cdt: *mut c_void) -> *mut c_void;
+// Notice: coordinates are bounded, so that the products computed in orientation tests can never \
+//   overflow (poly2tri also adds a margin around the bounds of the polyline).
+const CHECK_COORDINATE_MAX: Scalar = 1e12;
 
-    fn p2t_triangles_free(triangles: *mut c_void);
-    fn p2t_triangles_count(triangles: *mut c_void) -> size_t;
-    fn p2t_triangles_get_triangle(triangles: *const c_void, idx: size_t) -> *const c_void;
+extern "C" {
+    fn p2t_get_last_error() -> *const c_char;
+
+    fn p2t_polyline_new(polygon_out: *mut *mut c_void) -> c_int;
+    fn p2t_polyline_free(polygon: *mut c_void) -> c_int;
+    fn p2t_polyline_add_point(polygon: *mut c_void, x: Scalar, y: Scalar) -> c_int;
+
+    fn p2t_cdt_new(polygon: *mut c_void, cdt_out: *mut *mut c_void) -> c_int;
+    fn p2t_cdt_add_hole(cdt: *mut c_void, polygon: *mut c_void) -> c_int;
+    fn p2t_cdt_free(cdt: *mut c_void) -> c_int;
+    fn p2t_cdt_triangulate(cdt: *mut c_void) -> c_int;
+    fn p2t_cdt_get_triangles(cdt: *mut c_void, triangles_out: *mut *mut c_void) -> c_int;
+
+    fn p2t_triangles_free(triangles: *mut c_void) -> c_int;
+    fn p2t_triangles_count(triangles: *mut c_void, count_out: *mut size_t) -> c_int;
+    fn p2t_triangles_get_triangle(
+        triangles: *const c_void,
+        idx: size_t,
+        triangle_out: *mut *const c_void,
+    ) -> c_int;
 
     fn p2t_triangle_get_point(
         triangle: *const c_void,
         idx: size_t,
         x_out: *mut Scalar,
         y_out: *mut Scalar,
-    );
+    ) -> c_int;
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TriangulateError(pub(crate) String);
+
+#[derive(Default)]
+pub(crate) struct Triangulator {
+    points: Vec<[Scalar; 2]>,
+    rings: Vec<(usize, usize)>,
+    edges: Vec<(usize, usize)>,
+    sweep_order: Vec<usize>,
+    active: Vec<usize>,
 }
 
 pub(crate) struct Polygon {
     ll: *mut c_void,
 }
 
-pub(crate) struct CDT {
+pub(crate) struct Cdt {
     ll: *mut c_void,
 }
 
 pub(crate) struct TriangleVec {
     ll: *mut c_void,
+    size: usize,
 
     #[allow(dead_code)]
-    cdt: CDT,
+    cdt: Cdt,
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -57,32 +89,284 @@ pub(crate) struct Triangle {
     pub(crate) points: [[Scalar; 2]; 3],
 }
 
-impl Polygon {
-    pub(crate) fn new() -> Polygon {
-        unsafe {
-            Polygon {
-                ll: p2t_polyline_new(),
+impl TriangulateError {
+    fn last() -> Self {
+        // Acquire the reason of the last failure (retained per-thread in the binding)
+        let reason = unsafe { CStr::from_ptr(p2t_get_last_error()) };
+
+        TriangulateError(reason.to_string_lossy().into_owned())
+    }
+}
+
+impl Triangulator {
+    // Notice: the triangulator is meant to be re-used across calls, as all its buffers keep \
+    //   their allocated capacity around (this avoids allocating on every frame).
+    pub(crate) fn triangulate<'a, I, H>(
+        &mut self,
+        points: I,
+        holes: H,
+    ) -> Result<TriangleVec, TriangulateError>
+    where
+        I: Iterator<Item = &'a [Scalar; 2]>,
+        H: IntoIterator<Item = &'a [[Scalar; 2]]>,
+    {
+        self.points.clear();
+        self.rings.clear();
+        self.edges.clear();
+
+        self.load(points);
+
+        for hole in holes {
+            self.load(hole.iter());
+        }
+
+        // Reject degenerate polylines before they reach poly2tri, which either throws or asserts \
+        //   on those (asserting would abort the whole process)
+        self.check()?;
+
+        let (outline_start, outline_end) = self.rings[0];
+
+        let mut cdt = Cdt::new(Polygon::from_iterator(
+            self.points[outline_start..outline_end].iter(),
+        )?)?;
+
+        for (start, end) in self.rings.iter().skip(1) {
+            cdt.add_hole(Polygon::from_iterator(self.points[*start..*end].iter())?)?;
+        }
+
+        cdt.triangulate()
+    }
+
+    fn load<'a, I>(&mut self, points: I)
+    where
+        I: Iterator<Item = &'a [Scalar; 2]>,
+    {
+        let (ring, start) = (self.rings.len(), self.points.len());
+
+        self.points.extend(points);
+
+        let end = self.points.len();
+
+        self.rings.push((start, end));
+
+        // Map each edge (identified by the index of its starting point) to its polyline and to \
+        //   the index of its ending point
+        self.edges.extend(
+            (start..end).map(|index| (ring, if index + 1 == end { start } else { index + 1 })),
+        );
+    }
+
+    fn check(&mut self) -> Result<(), TriangulateError> {
+        let Self {
+            points,
+            rings,
+            edges,
+            sweep_order,
+            active,
+        } = self;
+
+        for (start, end) in rings.iter().copied() {
+            let ring = &points[start..end];
+
+            if ring.len() < 3 {
+                return Err(TriangulateError::from("polyline has less than 3 points"));
+            }
+
+            // Notice: this also rejects NaN coordinates, as they never compare
+            if !ring
+                .iter()
+                .flatten()
+                .all(|value| value.abs() <= CHECK_COORDINATE_MAX)
+            {
+                return Err(TriangulateError::from("polyline has out of range points"));
+            }
+
+            // Notice: repeated consecutive points are also collinear with their neighbors
+            for index in 0..ring.len() {
+                if Self::orientation(
+                    &ring[(index + ring.len() - 1) % ring.len()],
+                    &ring[index],
+                    &ring[(index + 1) % ring.len()],
+                ) == 0
+                {
+                    return Err(TriangulateError::from(
+                        "polyline has repeated or collinear points",
+                    ));
+                }
+            }
+
+            if Self::area(ring).abs() < CHECK_EPSILON {
+                return Err(TriangulateError::from("polyline has zero area"));
             }
         }
+
+        // Sweep edges from left to right, and check that no edge touches another edge, apart \
+        //   from consecutive edges of the same polyline which share a point (this rejects \
+        //   self-intersecting polylines, as well as repeated points and touching polylines)
+        let (edge_start, edge_end) = (
+            |index: usize| points[index][0].min(points[edges[index].1][0]),
+            |index: usize| points[index][0].max(points[edges[index].1][0]),
+        );
+
+        sweep_order.clear();
+        sweep_order.extend(0..edges.len());
+
+        sweep_order.sort_unstable_by(|index, other_index| {
+            edge_start(*index)
+                .partial_cmp(&edge_start(*other_index))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        active.clear();
+
+        for index in sweep_order.iter().copied() {
+            let (index_ring, index_next) = edges[index];
+
+            // Retire edges that end before the current edge starts
+            active.retain(|active_index: &usize| edge_end(*active_index) >= edge_start(index));
+
+            for active_index in active.iter().copied() {
+                let (active_ring, active_next) = edges[active_index];
+
+                let consecutive = index_ring == active_ring
+                    && (index_next == active_index || active_next == index);
+
+                if !consecutive
+                    && Self::touches(
+                        &[points[index], points[index_next]],
+                        &[points[active_index], points[active_next]],
+                    )
+                {
+                    return Err(TriangulateError::from(if index_ring == active_ring {
+                        "polyline intersects itself"
+                    } else {
+                        "polylines intersect each other"
+                    }));
+                }
+            }
+
+            active.push(index);
+        }
+
+        // Check that holes lie within the outline, and not within other holes (as polylines do \
+        //   not touch, checking a single point of each hole is enough)
+        for (hole_index, (start, _)) in rings.iter().copied().enumerate().skip(1) {
+            if !Self::contains(&points[rings[0].0..rings[0].1], &points[start]) {
+                return Err(TriangulateError::from("hole lies outside of the polyline"));
+            }
+
+            for (other_index, (other_start, other_end)) in rings.iter().copied().enumerate().skip(1)
+            {
+                if other_index != hole_index
+                    && Self::contains(&points[other_start..other_end], &points[start])
+                {
+                    return Err(TriangulateError::from("hole lies within another hole"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn orientation(point: &[Scalar; 2], other: &[Scalar; 2], third: &[Scalar; 2]) -> i8 {
+        // Notice: this is computed the same way poly2tri does (ie. 'Orient2d')
+        let value = (point[0] - third[0]) * (other[1] - third[1])
+            - (point[1] - third[1]) * (other[0] - third[0]);
+
+        if value > CHECK_EPSILON {
+            1
+        } else if value < -CHECK_EPSILON {
+            -1
+        } else {
+            0
+        }
+    }
+
+    fn touches(edge: &[[Scalar; 2]; 2], other: &[[Scalar; 2]; 2]) -> bool {
+        let within = |edge: &[[Scalar; 2]; 2], point: &[Scalar; 2]| {
+            point[0] >= edge[0][0].min(edge[1][0])
+                && point[0] <= edge[0][0].max(edge[1][0])
+                && point[1] >= edge[0][1].min(edge[1][1])
+                && point[1] <= edge[0][1].max(edge[1][1])
+        };
+
+        let (edge_start, edge_end, other_start, other_end) = (
+            Self::orientation(&edge[0], &edge[1], &other[0]),
+            Self::orientation(&edge[0], &edge[1], &other[1]),
+            Self::orientation(&other[0], &other[1], &edge[0]),
+            Self::orientation(&other[0], &other[1], &edge[1]),
+        );
+
+        // Edges either cross, or an end point of an edge lies on the other edge
+        (edge_start * edge_end < 0 && other_start * other_end < 0)
+            || (edge_start == 0 && within(edge, &other[0]))
+            || (edge_end == 0 && within(edge, &other[1]))
+            || (other_start == 0 && within(other, &edge[0]))
+            || (other_end == 0 && within(other, &edge[1]))
+    }
+
+    fn area(ring: &[[Scalar; 2]]) -> Scalar {
+        // Shoelace formula (polylines are implicitly closed)
+        (0..ring.len())
+            .map(|index| {
+                let (point, next_point) = (ring[index], ring[(index + 1) % ring.len()]);
+
+                point[0] * next_point[1] - next_point[0] * point[1]
+            })
+            .sum::<Scalar>()
+            / 2.0
     }
 
-    pub(crate) fn from_iterator<'a, I>(points: I) -> Polygon
+    fn contains(ring: &[[Scalar; 2]], point: &[Scalar; 2]) -> bool {
+        // Ray casting (the point is known not to lie on an edge)
+        let mut inside = false;
+
+        for index in 0..ring.len() {
+            let (start, end) = (ring[index], ring[(index + 1) % ring.len()]);
+
+            if (start[1] > point[1]) != (end[1] > point[1])
+                && point[0]
+                    < start[0] + (point[1] - start[1]) / (end[1] - start[1]) * (end[0] - start[0])
+            {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+}
+
+impl From<&str> for TriangulateError {
+    fn from(reason: &str) -> Self {
+        TriangulateError(reason.to_string())
+    }
+}
+
+impl Polygon {
+    pub(crate) fn new() -> Result<Polygon, TriangulateError> {
+        let mut ll = ptr::null_mut();
+
+        unsafe { check(p2t_polyline_new(&mut ll))? };
+
+        Ok(Polygon { ll })
+    }
+
+    pub(crate) fn from_iterator<'a, I>(points: I) -> Result<Polygon, TriangulateError>
     where
         I: Iterator<Item = &'a [Scalar; 2]>,
     {
-        let mut rv = Polygon::new();
+        let mut rv = Polygon::new()?;
 
         for point in points {
-            rv.add_point(point[0], point[1]);
+            rv.add_point(point[0], point[1])?;
         }
 
-        rv
+        Ok(rv)
     }
 
-    pub(crate) fn add_point(&mut self, x: Scalar, y: Scalar) {
-        unsafe {
-            p2t_polyline_add_point(self.ll, x, y);
-        }
+    pub(crate) fn add_point(&mut self, x: Scalar, y: Scalar) -> Result<(), TriangulateError> {
+        unsafe { check(p2t_polyline_add_point(self.ll, x, y)) }
     }
 }
 
@@ -94,31 +378,53 @@ impl Drop for Polygon {
     }
 }
 
-impl CDT {
-    pub(crate) fn new(polygon: Polygon) -> CDT {
-        unsafe {
-            let rv = CDT {
-                ll: p2t_cdt_new(polygon.ll),
-            };
+impl Cdt {
+    pub(crate) fn new(polygon: Polygon) -> Result<Cdt, TriangulateError> {
+        let mut ll = ptr::null_mut();
 
-            mem::forget(polygon);
+        // Notice: if the CDT could not be created, the polygon was not consumed and thus it \
+        //   must be dropped there (which frees it).
+        unsafe { check(p2t_cdt_new(polygon.ll, &mut ll))? };
 
-            rv
-        }
+        mem::forget(polygon);
+
+        Ok(Cdt { ll })
     }
 
-    pub(crate) fn triangulate(self) -> TriangleVec {
+    pub(crate) fn add_hole(&mut self, polygon: Polygon) -> Result<(), TriangulateError> {
+        // Notice: if the hole could not be added, the polygon was not consumed and thus it must \
+        //   be dropped there (which frees it).
+        unsafe { check(p2t_cdt_add_hole(self.ll, polygon.ll))? };
+
+        mem::forget(polygon);
+
+        Ok(())
+    }
+
+    pub(crate) fn triangulate(self) -> Result<TriangleVec, TriangulateError> {
+        let (mut ll, mut size) = (ptr::null_mut(), 0);
+
         unsafe {
-            p2t_cdt_triangulate(self.ll);
+            check(p2t_cdt_triangulate(self.ll))?;
+            check(p2t_cdt_get_triangles(self.ll, &mut ll))?;
+
+            // Wrap triangles right away, so that they get freed if counting fails
+            let mut triangles = TriangleVec {
+                cdt: self,
+                ll,
+                size: 0,
+            };
 
-            let ll = p2t_cdt_get_triangles(self.ll);
+            check(p2t_triangles_count(triangles.ll, &mut size))?;
 
-            TriangleVec { cdt: self, ll }
+            triangles.size = size as usize;
+
+            Ok(triangles)
         }
     }
 }
 
-impl Drop for CDT {
+impl Drop for Cdt {
     fn drop(&mut self) {
         unsafe {
             p2t_cdt_free(self.ll);
@@ -128,10 +434,10 @@ impl Drop for CDT {
 
 impl TriangleVec {
     pub(crate) fn size(&self) -> usize {
-        unsafe { p2t_triangles_count(self.ll) as usize }
+        self.size
     }
 
-    pub(crate) fn get_triangle(&self, idx: usize) -> Triangle {
+    pub(crate) fn get_triangle(&self, idx: usize) -> Result<Triangle, TriangulateError> {
         assert!(idx < self.size(), "Out of range");
 
         let mut p0 = [0.0; 2];
@@ -139,16 +445,18 @@ impl TriangleVec {
         let mut p2 = [0.0; 2];
 
         unsafe {
-            let tri = p2t_triangles_get_triangle(self.ll, idx as size_t);
+            let mut tri = ptr::null();
+
+            check(p2t_triangles_get_triangle(self.ll, idx as size_t, &mut tri))?;
 
-            p2t_triangle_get_point(tri, 0, &mut p0[0], &mut p0[1]);
-            p2t_triangle_get_point(tri, 1, &mut p1[0], &mut p1[1]);
-            p2t_triangle_get_point(tri, 2, &mut p2[0], &mut p2[1]);
+            check(p2t_triangle_get_point(tri, 0, &mut p0[0], &mut p0[1]))?;
+            check(p2t_triangle_get_point(tri, 1, &mut p1[0], &mut p1[1]))?;
+            check(p2t_triangle_get_point(tri, 2, &mut p2[0], &mut p2[1]))?;
         }
 
-        Triangle {
+        Ok(Triangle {
             points: [p0, p1, p2],
-        }
+        })
     }
 }
 
@@ -160,9 +468,154 @@ impl Drop for TriangleVec {
     }
 }
 
-pub(crate) fn triangulate_points<'a, I>(points: I) -> TriangleVec
-where
-    I: Iterator<Item = &'a [Scalar; 2]>,
-{
-    CDT::new(Polygon::from_iterator(points)).triangulate()
+#[inline(always)]
+fn check(status: c_int) -> Result<(), TriangulateError> {
+    if status == STATUS_OK {
+        Ok(())
+    } else {
+        Err(TriangulateError::last())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangulate(points: &[[Scalar; 2]]) -> Result<usize, TriangulateError> {
+        Triangulator::default()
+            .triangulate(points.iter(), std::iter::empty())
+            .map(|triangles| triangles.size())
+    }
+
+    #[test]
+    fn it_triangulates_simple_polygons() {
+        assert_eq!(
+            triangulate(&[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]).ok(),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn it_fails_on_repeated_points() {
+        assert!(triangulate(&[[0.0, 0.0], [10.0, 0.0], [10.0, 0.0], [0.0, 10.0]]).is_err());
+        assert!(triangulate(&[[0.0, 0.0], [10.0, 0.0], [0.0, 10.0], [0.0, 0.0]]).is_err());
+    }
+
+    #[test]
+    fn it_fails_on_too_few_points() {
+        assert!(triangulate(&[[0.0, 0.0], [10.0, 0.0]]).is_err());
+    }
+
+    #[test]
+    fn it_fails_on_collinear_points() {
+        assert!(triangulate(&[[0.0, 0.0], [5.0, 0.0], [10.0, 0.0], [0.0, 10.0]]).is_err());
+        assert!(triangulate(&[[0.0, 0.0], [5.0, 5.0], [10.0, 10.0]]).is_err());
+    }
+
+    #[test]
+    fn it_fails_on_self_touching_polygons() {
+        // Crossing edges (bowtie), and a point touching a non-consecutive edge
+        assert!(triangulate(&[[0.0, 0.0], [10.0, 10.0], [10.0, 0.0], [0.0, 10.0]]).is_err());
+        assert!(triangulate(&[
+            [0.0, 0.0],
+            [10.0, 0.0],
+            [10.0, 10.0],
+            [5.0, 0.0],
+            [0.0, 10.0]
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn it_does_not_abort_on_degenerate_polygons() {
+        let mut random = 0x2545f4914f6cdd1d_u64;
+
+        let mut next = |max: u64| {
+            // Xorshift generator (seeded, so that test runs are reproducible)
+            random ^= random << 13;
+            random ^= random >> 7;
+            random ^= random << 17;
+
+            random % max
+        };
+
+        let (mut triangulated, mut triangulator) = (0, Triangulator::default());
+
+        for _ in 0..5_000 {
+            // Random polylines on a small grid, which makes repeated points, collinear points, \
+            //   zero areas and touching or crossing edges frequent
+            let mut rings: Vec<Vec<[Scalar; 2]>> = (0..(1 + next(3)))
+                .map(|_| {
+                    (0..(2 + next(6)))
+                        .map(|_| [next(5) as Scalar, next(5) as Scalar])
+                        .collect()
+                })
+                .collect();
+
+            // Sprinkle some out of range values
+            if next(10) == 0 {
+                let special = [Scalar::NAN, Scalar::INFINITY, 1e300, -1e300][next(4) as usize];
+                let ring = next(rings.len() as u64) as usize;
+                let point = next(rings[ring].len() as u64) as usize;
+
+                rings[ring][point][next(2) as usize] = special;
+            }
+
+            let holes = rings[1..].iter().map(Vec::as_slice);
+
+            if let Ok(triangles) = triangulator.triangulate(rings[0].iter(), holes) {
+                // Accepted polylines are valid, thus triangles cover the outline minus its holes
+                let area: Scalar = (0..triangles.size())
+                    .map(|index| {
+                        Triangulator::area(&triangles.get_triangle(index).unwrap().points).abs()
+                    })
+                    .sum();
+                let expected_area = Triangulator::area(&rings[0]).abs()
+                    - rings[1..]
+                        .iter()
+                        .map(|hole| Triangulator::area(hole).abs())
+                        .sum::<Scalar>();
+
+                assert!((area - expected_area).abs() < 1e-9, "{:?}", rings);
+
+                triangulated += 1;
+            }
+        }
+
+        assert!(triangulated > 100);
+
+        // Extreme values, which used to reach poly2tri as-is
+        assert!(triangulate(&[[0.0, 0.0], [1e300, 0.0], [0.0, 1e-300], [-1e300, 0.0]]).is_err());
+    }
+
+    #[test]
+    fn it_fails_on_invalid_holes() {
+        let outline = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        let hole = [[2.0, 2.0], [2.0, 2.0], [4.0, 4.0]];
+
+        assert!(Triangulator::default()
+            .triangulate(outline.iter(), std::iter::once(&hole[..]))
+            .is_err());
+
+        // Holes touching the outline, lying outside of the outline, or within another hole
+        for holes in [
+            &[&[[0.0, 0.0], [4.0, 2.0], [2.0, 4.0]][..]][..],
+            &[&[[20.0, 20.0], [24.0, 22.0], [22.0, 24.0]][..]][..],
+            &[
+                &[[1.0, 1.0], [9.0, 1.0], [9.0, 9.0], [1.0, 9.0]][..],
+                &[[2.0, 2.0], [4.0, 2.0], [2.0, 4.0]][..],
+            ][..],
+        ] {
+            assert!(Triangulator::default()
+                .triangulate(outline.iter(), holes.iter().copied())
+                .is_err());
+        }
+
+        assert!(Triangulator::default()
+            .triangulate(
+                outline.iter(),
+                std::iter::once(&[[2.0, 2.0], [4.0, 2.0], [2.0, 4.0]][..])
+            )
+            .is_ok());
+    }
 }
@@ -30,14 +30,40 @@ impl From<&BackendColor> for Color {
     }
 }
 
-impl Into<conrod::color::Color> for Color {
+impl From<Color> for conrod::color::Color {
     #[inline(always)]
-    fn into(self) -> conrod::color::Color {
-        self.0
+    fn from(item: Color) -> Self {
+        item.0
     }
 }
 
 impl Color {
+    #[inline(always)]
+    pub(crate) fn to_srgb_components(color: &conrod::color::Color) -> ((u8, u8, u8), f32) {
+        let conrod::color::Rgba(r, g, b, a) = color.to_rgb();
+
+        // Revert the alpha channel transform that was applied when converting from a backend \
+        //   color, so that the original alpha value is returned.
+        (
+            (
+                (r * 255.0).round() as u8,
+                (g * 255.0).round() as u8,
+                (b * 255.0).round() as u8,
+            ),
+            Self::gamma_linear_to_srgb(a),
+        )
+    }
+
+    #[inline(always)]
+    fn gamma_linear_to_srgb(f: f32) -> f32 {
+        // See: https://en.wikipedia.org/wiki/SRGB
+        if f <= 0.0031308 {
+            f * 12.92
+        } else {
+            1.055 * f.powf(1.0 / 2.4) - 0.055
+        }
+    }
+
     #[inline(always)]
     fn gamma_srgb_to_linear(f: f32) -> f32 {
         // See: https://en.wikipedia.org/wiki/SRGB
@@ -6,6 +6,7 @@
 
 pub(crate) mod color;
 pub(crate) mod convert;
+pub(crate) mod monotone;
 pub(crate) mod path;
 pub(crate) mod position;
 pub(crate) mod shape;
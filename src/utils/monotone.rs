@@ -0,0 +1,371 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use conrod_core::position::Scalar as ConrodScalar;
+use num_traits::identities::Zero;
+
+type MonotoneFillValue = ConrodScalar;
+type MonotoneFillPoint = [MonotoneFillValue; 2];
+
+pub(crate) struct MonotoneFill<'a> {
+    path: &'a [MonotoneFillPoint],
+    chain_start: usize,
+    chain_turn: usize,
+}
+
+impl<'a> MonotoneFill<'a> {
+    /// Detect x-monotone polygons (eg. from `AreaSeries`, or confidence bands)
+    ///
+    /// Such polygons are made of two chains going from their leftmost point to their rightmost \
+    ///   point, that both only move in one direction on the X axis (eg. the upper and lower \
+    ///   bounds of a band). Polygons bounded by an horizontal baseline are a special case, where \
+    ///   one of the chains is the baseline itself.
+    #[allow(clippy::float_cmp)]
+    pub(crate) fn try_from(path: &'a [MonotoneFillPoint]) -> Result<Self, ()> {
+        // Ignore the closing point, if the path was explicitly closed
+        let path = match (path.first(), path.last()) {
+            (Some(first_point), Some(last_point))
+                if path.len() > 1 && first_point == last_point =>
+            {
+                &path[..(path.len() - 1)]
+            }
+            _ => path,
+        };
+
+        // Only proceed if we have enough points to form at least a triangle
+        if path.len() < 3 {
+            return Err(());
+        }
+
+        let x_min = path
+            .iter()
+            .fold(MonotoneFillValue::INFINITY, |x_min, point| {
+                x_min.min(point[0])
+            });
+
+        // Start walking from the leftmost point that leaves to the right (this skips over \
+        //   vertical edges on the left side of the polygon)
+        let chain_start = (0..path.len())
+            .find(|index| path[*index][0] == x_min && path[(*index + 1) % path.len()][0] > x_min)
+            .ok_or(())?;
+
+        let step_delta = |step: usize| {
+            let (point, next_point) = (
+                path[(chain_start + step) % path.len()],
+                path[(chain_start + step + 1) % path.len()],
+            );
+
+            next_point[0] - point[0]
+        };
+
+        // The first chain must never move backwards on the X axis, up to the rightmost point, \
+        //   while the second chain (going back to the leftmost point) must never move forwards
+        let chain_turn = (0..path.len())
+            .find(|step| step_delta(*step) < MonotoneFillValue::zero())
+            .ok_or(())?;
+
+        if (chain_turn..path.len()).all(|step| step_delta(step) <= MonotoneFillValue::zero()) {
+            Ok(Self {
+                path,
+                chain_start,
+                chain_turn,
+            })
+        } else {
+            Err(())
+        }
+    }
+
+    #[allow(clippy::float_cmp)]
+    pub(crate) fn triangulate<F>(&self, mut on_triangle: F)
+    where
+        F: FnMut(&[MonotoneFillPoint; 3]),
+    {
+        let mut emit = |triangle: [MonotoneFillPoint; 3]| {
+            // Skip empty triangles (eg. when both chains meet)
+            if Self::cross(&triangle) != MonotoneFillValue::zero() {
+                on_triangle(&triangle);
+            }
+        };
+
+        // Both chains are walked from left to right (the second chain is walked backwards)
+        let (upper_size, lower_size) = (self.chain_turn, self.path.len() - self.chain_turn);
+
+        let upper_point = |index: usize| self.path[(self.chain_start + index) % self.path.len()];
+        let lower_point = |index: usize| {
+            self.path[(self.chain_start + self.path.len() - index) % self.path.len()]
+        };
+
+        let (mut upper_index, mut lower_index) = (0, 0);
+
+        // Fill the area between both chains, over each X interval where both chains are made of \
+        //   a single segment, with a quad (ie. two triangles), or with two triangles if the \
+        //   chains cross each other within the interval.
+        // Notice: as a vertical line only crosses the polygon outline once on each chain, the \
+        //   filled area is the one between both chains, even if the polygon self-intersects.
+        while upper_index < upper_size && lower_index < lower_size {
+            let (upper, lower) = (
+                [upper_point(upper_index), upper_point(upper_index + 1)],
+                [lower_point(lower_index), lower_point(lower_index + 1)],
+            );
+
+            // Vertical segments cover no area
+            if upper[0][0] == upper[1][0] {
+                upper_index += 1;
+
+                continue;
+            }
+            if lower[0][0] == lower[1][0] {
+                lower_index += 1;
+
+                continue;
+            }
+
+            let (x_start, x_end) = (upper[0][0].max(lower[0][0]), upper[1][0].min(lower[1][0]));
+
+            if x_end > x_start {
+                let (upper_start, upper_end, lower_start, lower_end) = (
+                    [x_start, Self::y_at(&upper, x_start)],
+                    [x_end, Self::y_at(&upper, x_end)],
+                    [x_start, Self::y_at(&lower, x_start)],
+                    [x_end, Self::y_at(&lower, x_end)],
+                );
+
+                let (start_height, end_height) =
+                    (upper_start[1] - lower_start[1], upper_end[1] - lower_end[1]);
+
+                if start_height * end_height < MonotoneFillValue::zero() {
+                    let crossing_x =
+                        x_start + (x_end - x_start) * start_height / (start_height - end_height);
+                    let crossing = [crossing_x, Self::y_at(&upper, crossing_x)];
+
+                    emit([upper_start, crossing, lower_start]);
+                    emit([crossing, upper_end, lower_end]);
+                } else {
+                    emit([upper_start, upper_end, lower_end]);
+                    emit([upper_start, lower_end, lower_start]);
+                }
+            }
+
+            // Move to the next segment on the chains that end first
+            let (upper_ends, lower_ends) = (upper[1][0] <= lower[1][0], lower[1][0] <= upper[1][0]);
+
+            if upper_ends {
+                upper_index += 1;
+            }
+            if lower_ends {
+                lower_index += 1;
+            }
+        }
+    }
+
+    #[inline(always)]
+    #[allow(clippy::float_cmp)]
+    fn y_at(segment: &[MonotoneFillPoint; 2], x: MonotoneFillValue) -> MonotoneFillValue {
+        // Notice: segment ends are returned as-is, so that original points are kept exact.
+        if x == segment[0][0] {
+            segment[0][1]
+        } else if x == segment[1][0] {
+            segment[1][1]
+        } else {
+            segment[0][1]
+                + (segment[1][1] - segment[0][1]) * (x - segment[0][0])
+                    / (segment[1][0] - segment[0][0])
+        }
+    }
+
+    #[inline(always)]
+    fn cross(triangle: &[MonotoneFillPoint; 3]) -> MonotoneFillValue {
+        (triangle[1][0] - triangle[0][0]) * (triangle[2][1] - triangle[0][1])
+            - (triangle[1][1] - triangle[0][1]) * (triangle[2][0] - triangle[0][0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::triangulate;
+
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, max: u64) -> MonotoneFillValue {
+            // Xorshift generator (seeded, so that test runs are reproducible)
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            (self.0 % max) as MonotoneFillValue
+        }
+
+        fn chain(&mut self, width: u64, y_min: u64, y_range: u64) -> Vec<MonotoneFillPoint> {
+            let mut xs: Vec<_> = (0..(1 + self.next(20) as usize))
+                .map(|_| 1.0 + self.next(width - 1))
+                .collect();
+
+            xs.sort_by(|x, other_x| x.partial_cmp(other_x).unwrap());
+            xs.dedup();
+
+            xs.into_iter()
+                .map(|x| [x, y_min as MonotoneFillValue + self.next(y_range)])
+                .collect()
+        }
+    }
+
+    fn band(upper: &[MonotoneFillPoint], lower: &[MonotoneFillPoint]) -> Vec<MonotoneFillPoint> {
+        let width = 50.0;
+
+        let mut path = vec![[0.0, 100.0]];
+
+        path.extend_from_slice(upper);
+        path.push([width, 100.0]);
+        path.extend(lower.iter().rev());
+
+        path
+    }
+
+    fn triangles_of(path: &[MonotoneFillPoint]) -> Vec<[MonotoneFillPoint; 3]> {
+        let mut triangles = Vec::new();
+
+        MonotoneFill::try_from(path)
+            .expect("path is x-monotone")
+            .triangulate(|triangle| triangles.push(*triangle));
+
+        triangles
+    }
+
+    fn triangle_contains(triangle: &[MonotoneFillPoint; 3], point: &MonotoneFillPoint) -> bool {
+        let sides = [0, 1, 2].map(|index| {
+            let (start, end) = (triangle[index], triangle[(index + 1) % 3]);
+
+            (end[0] - start[0]) * (point[1] - start[1])
+                - (end[1] - start[1]) * (point[0] - start[0])
+                > 0.0
+        });
+
+        sides.iter().all(|side| *side) || sides.iter().all(|side| !*side)
+    }
+
+    fn path_contains(path: &[MonotoneFillPoint], point: &MonotoneFillPoint) -> bool {
+        // Ray casting (ie. even-odd rule)
+        let mut inside = false;
+
+        for index in 0..path.len() {
+            let (start, end) = (path[index], path[(index + 1) % path.len()]);
+
+            if (start[1] > point[1]) != (end[1] > point[1])
+                && point[0]
+                    < start[0] + (point[1] - start[1]) / (end[1] - start[1]) * (end[0] - start[0])
+            {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+
+    fn samples() -> impl Iterator<Item = MonotoneFillPoint> {
+        // Sample points are offset from integer coordinates, so that they never lie on edges
+        (0..25)
+            .flat_map(|x| (0..100).map(move |y| [x as f64 * 2.0 + 0.3137, y as f64 * 2.0 + 0.4419]))
+    }
+
+    fn coverage(triangles: &[[MonotoneFillPoint; 3]]) -> Vec<usize> {
+        samples()
+            .map(|point| {
+                triangles
+                    .iter()
+                    .filter(|triangle| triangle_contains(triangle, &point))
+                    .count()
+            })
+            .collect()
+    }
+
+    fn area(triangles: &[[MonotoneFillPoint; 3]]) -> MonotoneFillValue {
+        triangles
+            .iter()
+            .map(|triangle| MonotoneFill::cross(triangle).abs() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn it_detects_monotone_polygons() {
+        // Baseline-bounded area
+        assert!(MonotoneFill::try_from(&[[0.0, 10.0], [5.0, 2.0], [10.0, 10.0]]).is_ok());
+
+        // Band between two chains, explicitly closed
+        assert!(MonotoneFill::try_from(&[
+            [0.0, 5.0],
+            [5.0, 0.0],
+            [10.0, 5.0],
+            [5.0, 10.0],
+            [0.0, 5.0]
+        ])
+        .is_ok());
+
+        // Chain going backwards on the X axis
+        assert!(MonotoneFill::try_from(&[
+            [0.0, 10.0],
+            [6.0, 2.0],
+            [4.0, 4.0],
+            [10.0, 2.0],
+            [10.0, 10.0]
+        ])
+        .is_err());
+
+        // Polygon without any width
+        assert!(MonotoneFill::try_from(&[[5.0, 0.0], [5.0, 5.0], [5.0, 10.0]]).is_err());
+    }
+
+    #[test]
+    fn it_covers_the_polygon_area() {
+        let mut random = Random(0x2545f4914f6cdd1d);
+
+        for _ in 0..100 {
+            // Bands whose chains may cross each other (ie. self-intersecting polygons)
+            let path = band(&random.chain(50, 0, 200), &random.chain(50, 0, 200));
+
+            let expected: Vec<_> = samples()
+                .map(|point| path_contains(&path, &point) as usize)
+                .collect();
+
+            assert_eq!(coverage(&triangles_of(&path)), expected);
+        }
+    }
+
+    #[test]
+    fn it_covers_the_same_area_as_triangulation() {
+        let (mut random, mut compared) = (Random(0x9e3779b97f4a7c15), 0);
+
+        for index in 0..100 {
+            // Simple polygons, either bands or baseline-bounded areas
+            let path = if index % 2 == 0 {
+                band(&random.chain(50, 110, 90), &random.chain(50, 0, 90))
+            } else {
+                band(&random.chain(50, 110, 90), &[])
+            };
+
+            let fast_triangles = triangles_of(&path);
+
+            // Notice: poly2tri does not support some inputs (eg. collinear points), which are \
+            //   skipped there.
+            if let Ok(triangulation) =
+                triangulate::Triangulator::default().triangulate(path.iter(), std::iter::empty())
+            {
+                let triangles: Vec<_> = (0..triangulation.size())
+                    .map(|index| triangulation.get_triangle(index).unwrap().points)
+                    .collect();
+
+                assert!((area(&fast_triangles) - area(&triangles)).abs() < 1e-6);
+                assert_eq!(coverage(&fast_triangles), coverage(&triangles));
+
+                compared += 1;
+            }
+        }
+
+        assert!(compared > 50);
+    }
+}
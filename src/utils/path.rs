@@ -6,7 +6,7 @@
 
 use conrod_core::position::Scalar as ConrodScalar;
 
-pub(crate) type PathScalar = i32;
+pub(crate) type PathScalar = ConrodScalar;
 
 type PathSimplifierPointInner = [PathScalar; 2];
 type PathSimplifierPointOuter = [ConrodScalar; 2];
@@ -36,9 +36,10 @@ impl<I: Iterator<Item = PathSimplifierPointInner>> PathSimplifier<I> {
 impl<I: Iterator<Item = PathSimplifierPointInner>> Iterator for PathSimplifier<I> {
     type Item = PathSimplifierPointOuter;
 
+    #[allow(clippy::float_cmp)]
     fn next(&mut self) -> Option<Self::Item> {
         // Branch to source points iterator (exhaust next group)
-        while let Some(point) = self.source_points.next() {
+        for point in self.source_points.by_ref() {
             // Backtrack in points
             if let Some(point_before) = self.last_point {
                 // Retain current point as 'last point'
@@ -99,10 +100,7 @@ impl<I: Iterator<Item = PathSimplifierPointInner>> Iterator for PathSimplifier<I
                     }
 
                     if do_yield {
-                        return Some([
-                            point_before[0] as ConrodScalar,
-                            point_before[1] as ConrodScalar,
-                        ]);
+                        return Some(point_before);
                     }
                 }
             } else {
@@ -115,10 +113,522 @@ impl<I: Iterator<Item = PathSimplifierPointInner>> Iterator for PathSimplifier<I
         if let Some(last_point) = self.last_point {
             self.last_point = None;
 
-            return Some([last_point[0] as ConrodScalar, last_point[1] as ConrodScalar]);
+            return Some(last_point);
         }
 
         // Done painting all path points
         None
     }
 }
+
+pub(crate) struct PathReducer<I: Iterator<Item = PathSimplifierPointOuter>> {
+    source_points: I,
+    tolerance: ConrodScalar,
+    anchor_point: Option<PathSimplifierPointOuter>,
+    pending_point: Option<PathSimplifierPointOuter>,
+    pending_distance: ConrodScalar,
+    sleeve: Option<[ConrodScalar; 2]>,
+}
+
+impl<I: Iterator<Item = PathSimplifierPointOuter>> PathReducer<I> {
+    pub(crate) fn from(source_points: I, tolerance: ConrodScalar) -> Self {
+        Self {
+            source_points,
+            tolerance,
+            anchor_point: None,
+            pending_point: None,
+            pending_distance: 0.0,
+            sleeve: None,
+        }
+    }
+
+    fn accept(&mut self, point: PathSimplifierPointOuter) -> bool {
+        // Notice: this is a sleeve-fitting algorithm, where the sleeve is the angular sector \
+        //   (as seen from the anchor point) within which all skipped points stay within tolerance \
+        //   from the line going from the anchor point to the pending point. It runs in linear \
+        //   time, as opposed to Ramer-Douglas-Peucker.
+        let anchor_point = match self.anchor_point {
+            Some(anchor_point) => anchor_point,
+            None => return false,
+        };
+
+        let delta = [point[0] - anchor_point[0], point[1] - anchor_point[1]];
+        let distance = delta[0].hypot(delta[1]);
+
+        // Moving backwards towards the anchor would cut out a spike, which must be kept
+        if distance < self.pending_distance {
+            return false;
+        }
+
+        // Points far enough from the anchor restrict the sleeve (others do not restrict it, as \
+        //   they are already within tolerance from the anchor)
+        if distance > self.tolerance {
+            let angle = delta[1].atan2(delta[0]);
+            let half_width = (self.tolerance / distance).asin();
+
+            self.sleeve = match self.sleeve {
+                None => Some([angle - half_width, angle + half_width]),
+                Some(sleeve) => {
+                    // Wrap angle around the sleeve center
+                    let center = (sleeve[0] + sleeve[1]) / 2.0;
+                    let angle = center
+                        + (angle - center + std::f64::consts::PI)
+                            .rem_euclid(2.0 * std::f64::consts::PI)
+                        - std::f64::consts::PI;
+
+                    if angle < sleeve[0] || angle > sleeve[1] {
+                        return false;
+                    }
+
+                    Some([
+                        sleeve[0].max(angle - half_width),
+                        sleeve[1].min(angle + half_width),
+                    ])
+                }
+            };
+        }
+
+        self.pending_point = Some(point);
+        self.pending_distance = distance;
+
+        true
+    }
+}
+
+impl<I: Iterator<Item = PathSimplifierPointOuter>> Iterator for PathReducer<I> {
+    type Item = PathSimplifierPointOuter;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Pass points through as-is, if there is no tolerance
+        if self.tolerance <= 0.0 {
+            return self.source_points.next();
+        }
+
+        while let Some(point) = self.source_points.next() {
+            if self.anchor_point.is_none() {
+                // Always yield first point (it anchors the first segment)
+                self.anchor_point = Some(point);
+
+                return Some(point);
+            }
+
+            if !self.accept(point) {
+                // Point is out of the sleeve, yield pending point, which anchors the next segment
+                let pending_point = self.pending_point.take();
+
+                self.anchor_point = pending_point;
+                self.pending_distance = 0.0;
+                self.sleeve = None;
+
+                self.accept(point);
+
+                if pending_point.is_some() {
+                    return pending_point;
+                }
+            }
+        }
+
+        // End of the source points iterator, close path with the pending point
+        self.pending_point.take()
+    }
+}
+
+pub(crate) struct PathDecimator<I: Iterator<Item = PathSimplifierPointOuter>> {
+    source_points: I,
+    source_index: usize,
+    column: Option<PathDecimatorColumn>,
+    yield_points: [PathSimplifierPointOuter; 4],
+    yield_range: (usize, usize),
+}
+
+struct PathDecimatorColumn {
+    x: ConrodScalar,
+    first: (usize, PathSimplifierPointOuter),
+    min: (usize, PathSimplifierPointOuter),
+    max: (usize, PathSimplifierPointOuter),
+    last: (usize, PathSimplifierPointOuter),
+}
+
+impl<I: Iterator<Item = PathSimplifierPointOuter>> PathDecimator<I> {
+    pub(crate) fn from(source_points: I) -> Self {
+        Self {
+            source_points,
+            source_index: 0,
+            column: None,
+            yield_points: [[0.0; 2]; 4],
+            yield_range: (0, 0),
+        }
+    }
+
+    fn flush(&mut self, column: PathDecimatorColumn) {
+        // Yield the column envelope points in their original order (de-duplicating points \
+        //   that fill multiple roles, eg. the first point also being the minimum point)
+        let mut column_points = [column.first, column.min, column.max, column.last];
+
+        column_points.sort_unstable_by_key(|(index, _)| *index);
+
+        self.yield_range = (0, 0);
+
+        for (position, (index, point)) in column_points.iter().enumerate() {
+            if position == 0 || *index != column_points[position - 1].0 {
+                self.yield_points[self.yield_range.1] = *point;
+                self.yield_range.1 += 1;
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = PathSimplifierPointOuter>> Iterator for PathDecimator<I> {
+    type Item = PathSimplifierPointOuter;
+
+    #[allow(clippy::float_cmp)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Yield pending points from the last flushed column
+            if self.yield_range.0 < self.yield_range.1 {
+                self.yield_range.0 += 1;
+
+                return Some(self.yield_points[self.yield_range.0 - 1]);
+            }
+
+            if let Some(point) = self.source_points.next() {
+                let (index, x) = (self.source_index, point[0].floor());
+
+                self.source_index += 1;
+
+                match self.column {
+                    // Point falls in the current pixel column, update its envelope
+                    Some(ref mut column) if column.x == x => {
+                        if point[1] < column.min.1[1] {
+                            column.min = (index, point);
+                        }
+                        if point[1] > column.max.1[1] {
+                            column.max = (index, point);
+                        }
+
+                        column.last = (index, point);
+                    }
+                    // Point falls in another pixel column, flush the current one
+                    _ => {
+                        let column = self.column.replace(PathDecimatorColumn {
+                            x,
+                            first: (index, point),
+                            min: (index, point),
+                            max: (index, point),
+                            last: (index, point),
+                        });
+
+                        if let Some(column) = column {
+                            self.flush(column);
+                        }
+                    }
+                }
+            } else if let Some(column) = self.column.take() {
+                // End of the source points iterator, flush the last column
+                self.flush(column);
+            } else {
+                // Done yielding all path points
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use plotters::prelude::{BitMapBackend, ShapeStyle, BLACK};
+    use plotters_backend::DrawingBackend;
+
+    const SIZE: (u32, u32) = (200, 100);
+
+    fn random_walk(seed: u64, samples: usize) -> Vec<PathSimplifierPointOuter> {
+        // Xorshift generator (seeded, so that test runs are reproducible)
+        let (mut random, mut y) = (seed, 50);
+
+        (0..samples)
+            .map(|index| {
+                random ^= random << 13;
+                random ^= random >> 7;
+                random ^= random << 17;
+
+                y = (y + (random % 11) as i64 - 5).clamp(0, SIZE.1 as i64 - 1);
+
+                [
+                    (index * SIZE.0 as usize / samples) as ConrodScalar,
+                    y as ConrodScalar,
+                ]
+            })
+            .collect()
+    }
+
+    fn rasterize(points: &[PathSimplifierPointOuter]) -> Vec<u8> {
+        let mut buffer = vec![255; (SIZE.0 * SIZE.1 * 3) as usize];
+
+        {
+            let mut backend = BitMapBackend::with_buffer(&mut buffer, SIZE);
+
+            backend
+                .draw_path(
+                    points
+                        .iter()
+                        .map(|point| (point[0] as i32, point[1] as i32)),
+                    &ShapeStyle::from(&BLACK).stroke_width(1),
+                )
+                .unwrap();
+            backend.present().unwrap();
+        }
+
+        buffer
+    }
+
+    #[test]
+    fn it_rasterizes_like_the_full_path() {
+        for (seed, samples) in [(0x2545f4914f6cdd1d, 10_000), (0x9e3779b97f4a7c15, 1_000)] {
+            let points = random_walk(seed, samples);
+            let decimated: Vec<_> = PathDecimator::from(points.iter().copied()).collect();
+
+            let (decimated_pixels, pixels) = (rasterize(&decimated), rasterize(&points));
+
+            assert!(decimated.len() < points.len());
+            assert!(pixels.contains(&0));
+            assert!(decimated_pixels == pixels);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn it_keeps_at_most_4_points_per_column() {
+        let decimated: Vec<_> =
+            PathDecimator::from(random_walk(0x2545f4914f6cdd1d, 100_000).into_iter()).collect();
+
+        for column in 0..SIZE.0 {
+            let column_points = decimated
+                .iter()
+                .filter(|point| point[0].floor() == column as ConrodScalar)
+                .count();
+
+            assert!((1..=4).contains(&column_points));
+        }
+    }
+
+    fn reduce(
+        points: &[PathSimplifierPointOuter],
+        tolerance: ConrodScalar,
+    ) -> Vec<PathSimplifierPointOuter> {
+        PathReducer::from(points.iter().copied(), tolerance).collect()
+    }
+
+    fn segment_distance(
+        point: &PathSimplifierPointOuter,
+        segment: &[PathSimplifierPointOuter; 2],
+    ) -> ConrodScalar {
+        let (start, end) = (segment[0], segment[1]);
+        let (delta, length_squared) = (
+            [end[0] - start[0], end[1] - start[1]],
+            (end[0] - start[0]).powi(2) + (end[1] - start[1]).powi(2),
+        );
+
+        let along = if length_squared > 0.0 {
+            (((point[0] - start[0]) * delta[0] + (point[1] - start[1]) * delta[1]) / length_squared)
+                .clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        (point[0] - start[0] - along * delta[0]).hypot(point[1] - start[1] - along * delta[1])
+    }
+
+    fn orientation(
+        point: &PathSimplifierPointOuter,
+        other: &PathSimplifierPointOuter,
+        third: &PathSimplifierPointOuter,
+    ) -> ConrodScalar {
+        ((other[0] - point[0]) * (third[1] - point[1])
+            - (other[1] - point[1]) * (third[0] - point[0]))
+            .signum()
+    }
+
+    fn is_simple(ring: &[PathSimplifierPointOuter]) -> bool {
+        // Non-consecutive edges must not touch, while consecutive edges must not fold back
+        let edge = |index: usize| [ring[index], ring[(index + 1) % ring.len()]];
+
+        let touches = |edge: [PathSimplifierPointOuter; 2],
+                       other: [PathSimplifierPointOuter; 2]| {
+            segment_distance(&other[0], &edge) == 0.0
+                || segment_distance(&other[1], &edge) == 0.0
+                || segment_distance(&edge[0], &other) == 0.0
+                || segment_distance(&edge[1], &other) == 0.0
+                || (orientation(&edge[0], &edge[1], &other[0])
+                    * orientation(&edge[0], &edge[1], &other[1])
+                    < 0.0
+                    && orientation(&other[0], &other[1], &edge[0])
+                        * orientation(&other[0], &other[1], &edge[1])
+                        < 0.0)
+        };
+
+        ring.len() >= 3
+            && (0..ring.len()).all(|index| {
+                let (previous, point, next) = (
+                    ring[(index + ring.len() - 1) % ring.len()],
+                    ring[index],
+                    ring[(index + 1) % ring.len()],
+                );
+
+                // Consecutive edges going back on themselves overlap
+                let folds_back = orientation(&previous, &point, &next) == 0.0
+                    && (point[0] - previous[0]) * (next[0] - point[0])
+                        + (point[1] - previous[1]) * (next[1] - point[1])
+                        <= 0.0;
+
+                !folds_back
+                    && (index + 2..ring.len())
+                        .filter(|other_index| (other_index + 1) % ring.len() != index)
+                        .all(|other_index| !touches(edge(index), edge(other_index)))
+            })
+    }
+
+    #[test]
+    fn it_passes_points_through_without_tolerance() {
+        let points = random_walk(0x2545f4914f6cdd1d, 1_000);
+
+        assert_eq!(reduce(&points, 0.0), points);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn it_removes_collinear_points() {
+        let diagonal: Vec<_> = (0..=10)
+            .map(|index| [index as ConrodScalar, index as ConrodScalar])
+            .collect();
+        let horizontal: Vec<_> = (0..=10).map(|index| [index as ConrodScalar, 5.0]).collect();
+
+        assert_eq!(reduce(&diagonal, 0.5), vec![[0.0, 0.0], [10.0, 10.0]]);
+        assert_eq!(reduce(&horizontal, 0.5), vec![[0.0, 5.0], [10.0, 5.0]]);
+
+        // Staircase deviating less than the tolerance from a diagonal
+        let staircase: Vec<_> = (0..=20)
+            .map(|index| {
+                [
+                    (index / 2) as ConrodScalar,
+                    ((index + 1) / 2) as ConrodScalar,
+                ]
+            })
+            .collect();
+
+        assert_eq!(reduce(&staircase, 0.75), vec![[0.0, 0.0], [10.0, 10.0]]);
+    }
+
+    #[test]
+    fn it_keeps_spikes_and_extremes() {
+        // Spike rising from a flat line, and a path going back on itself
+        let spike = [
+            [0.0, 50.0],
+            [10.0, 50.0],
+            [11.0, 10.0],
+            [12.0, 50.0],
+            [30.0, 50.0],
+        ];
+        let back_and_forth = [[0.0, 0.0], [10.0, 0.0], [20.0, 0.0], [5.0, 0.0]];
+
+        assert_eq!(reduce(&spike, 0.5), spike.to_vec());
+        assert_eq!(
+            reduce(&back_and_forth, 0.5),
+            vec![[0.0, 0.0], [20.0, 0.0], [5.0, 0.0]]
+        );
+
+        // Extremes of a random walk are kept (ie. its minimum and maximum values)
+        let points = random_walk(0x9e3779b97f4a7c15, 10_000);
+        let reduced = reduce(&points, 0.5);
+
+        for extreme in [
+            points
+                .iter()
+                .map(|point| point[1])
+                .fold(ConrodScalar::INFINITY, ConrodScalar::min),
+            points
+                .iter()
+                .map(|point| point[1])
+                .fold(ConrodScalar::NEG_INFINITY, ConrodScalar::max),
+        ] {
+            assert!(reduced
+                .iter()
+                .any(|point| (point[1] - extreme).abs() <= 0.5));
+        }
+    }
+
+    #[test]
+    fn it_deviates_less_than_tolerance() {
+        for (seed, tolerance) in [(0x2545f4914f6cdd1d, 0.5), (0x9e3779b97f4a7c15, 2.0)] {
+            // Spread points over fractional abscissas, so that all points are distinct
+            let points: Vec<_> = random_walk(seed, 10_000)
+                .into_iter()
+                .enumerate()
+                .map(|(index, point)| [index as ConrodScalar * 0.08, point[1]])
+                .collect();
+            let reduced = reduce(&points, tolerance);
+
+            assert!(reduced.len() < points.len());
+            assert_eq!(reduced.first(), points.first());
+            assert_eq!(reduced.last(), points.last());
+
+            // Kept points are source points, and all skipped points lie within tolerance from \
+            //   the segment joining the kept points around them
+            let mut cursor = 0;
+
+            for segment in reduced.windows(2) {
+                let start = cursor;
+
+                cursor += points[cursor..]
+                    .iter()
+                    .position(|point| point == &segment[1])
+                    .unwrap();
+
+                for point in &points[start..cursor] {
+                    assert!(segment_distance(point, &[segment[0], segment[1]]) <= tolerance + 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_keeps_simple_rings_simple() {
+        let (mut random, mut reduced_points) = (0x2545f4914f6cdd1du64, 0);
+
+        for _ in 0..200 {
+            // Star-shaped rings, with noisy radii (as filled areas around a center, eg. radar charts)
+            let samples = 10 + (random % 500) as usize;
+
+            let ring: Vec<_> = (0..samples)
+                .map(|index| {
+                    random ^= random << 13;
+                    random ^= random >> 7;
+                    random ^= random << 17;
+
+                    let (angle, radius) = (
+                        index as ConrodScalar / samples as ConrodScalar * std::f64::consts::TAU,
+                        40.0 + (random % 20) as ConrodScalar,
+                    );
+
+                    [
+                        (100.0 + angle.cos() * radius).round(),
+                        (100.0 + angle.sin() * radius).round(),
+                    ]
+                })
+                .collect();
+
+            let ring: Vec<_> = PathSimplifier::from(ring.into_iter()).collect();
+
+            if is_simple(&ring) {
+                let reduced = reduce(&ring, 0.5);
+
+                assert!(is_simple(&reduced), "{:?}", ring);
+
+                reduced_points += ring.len() - reduced.len();
+            }
+        }
+
+        assert!(reduced_points > 0);
+    }
+}
@@ -7,45 +7,131 @@
 use conrod_core::{self as conrod, position::Scalar as ConrodScalar};
 use plotters_backend::BackendCoord;
 
-use super::path::PathScalar;
+use crate::transform::ConrodBackendTransform;
 
-type PositionScalar = i32;
+// Notice: parent coordinates are kept as floating point numbers, as Conrod layouts may place \
+//   the parent on fractional coordinates (eg. centered canvases), which would otherwise make \
+//   plots jitter by one pixel upon resizing the window.
+type PositionScalar = ConrodScalar;
 
 pub(crate) struct PositionParent {
     x_start: PositionScalar,
     y_end: PositionScalar,
+    transform: Option<ConrodBackendTransform>,
 }
 
 impl PositionParent {
     #[inline(always)]
-    pub(crate) fn from(ui: &conrod::UiCell, parent: conrod::widget::Id) -> Option<Self> {
-        if let Some(parent_rect) = ui.rect_of(parent) {
-            Some(Self {
-                x_start: parent_rect.x.start as PositionScalar,
-                y_end: parent_rect.y.end as PositionScalar,
-            })
-        } else {
-            None
+    pub(crate) fn from(ui: &conrod::Ui, parent: conrod::widget::Id) -> Option<Self> {
+        ui.rect_of(parent).map(|parent_rect| Self {
+            x_start: parent_rect.x.start,
+            y_end: parent_rect.y.end,
+            transform: None,
+        })
+    }
+
+    #[inline(always)]
+    pub(crate) fn with_transform(mut self, transform: Option<ConrodBackendTransform>) -> Self {
+        self.transform = transform;
+
+        self
+    }
+
+    #[inline(always)]
+    pub(crate) fn transform_point(&self, point: &[ConrodScalar; 2]) -> [ConrodScalar; 2] {
+        // Apply the global transform to a relative-positioned point (if any)
+        match self.transform {
+            Some(ref transform) => transform.apply(*point),
+            None => *point,
         }
     }
 
     #[inline(always)]
-    pub(crate) fn abs_point_conrod_scalar(&self, point: &BackendCoord) -> [ConrodScalar; 2] {
-        // Convert relative-positioned point (in backend coordinates) to absolute coordinates in \
-        //   the full rendering space.
-        [
-            (point.0 + self.x_start) as ConrodScalar,
-            (-point.1 + self.y_end) as ConrodScalar,
-        ]
+    pub(crate) fn transform_extents(&self, radius: ConrodScalar) -> [ConrodScalar; 2] {
+        match self.transform {
+            Some(ref transform) => transform.extents(radius),
+            None => [radius, radius],
+        }
     }
 
     #[inline(always)]
-    pub(crate) fn abs_point_path_simplifier(&self, point: &BackendCoord) -> [PathScalar; 2] {
-        // Convert relative-positioned point (in backend coordinates) to absolute coordinates in \
-        //   the full rendering space.
-        [
-            (point.0 + self.x_start) as PathScalar,
-            (-point.1 + self.y_end) as PathScalar,
-        ]
+    pub(crate) fn abs_point_geometry(&self, point: &[ConrodScalar; 2]) -> [ConrodScalar; 2] {
+        // Convert relative-positioned point (in prepared geometry coordinates) to absolute \
+        //   coordinates in the full rendering space (applying the global transform, if any).
+        self.abs_point_transformed(&self.transform_point(point))
+    }
+
+    #[inline(always)]
+    pub(crate) fn abs_point_transformed(&self, point: &[ConrodScalar; 2]) -> [ConrodScalar; 2] {
+        // Convert relative-positioned point (with the global transform already applied) to \
+        //   absolute coordinates in the full rendering space.
+        [point[0] + self.x_start, -point[1] + self.y_end]
+    }
+
+    #[inline(always)]
+    pub(crate) fn rel_point_backend_coord(&self, point: &[ConrodScalar; 2]) -> BackendCoord {
+        // Convert absolute-positioned point (in the full rendering space) to relative \
+        //   coordinates (in backend coordinates); this is the inverse of the conversion applied \
+        //   in 'abs_point_geometry()', rounded down to the pixel that contains the point.
+        // Notice: converting a floating point number to an integer saturates, thus points far \
+        //   outside of the backend coordinates space are clipped to its bounds.
+        (
+            (point[0] - self.x_start).floor() as i32,
+            (-point[1] + self.y_end).floor() as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::geometry;
+
+    const EXTREME_COORDS: [BackendCoord; 5] = [
+        (i32::MIN, i32::MIN),
+        (i32::MIN, i32::MAX),
+        (i32::MAX, i32::MIN),
+        (i32::MAX, i32::MAX),
+        (0, 0),
+    ];
+
+    fn parent() -> PositionParent {
+        // Parent placed on fractional coordinates (eg. a centered canvas)
+        PositionParent {
+            x_start: -400.5,
+            y_end: 240.5,
+            transform: None,
+        }
+    }
+
+    #[test]
+    fn it_converts_extreme_coordinates_back_and_forth() {
+        let position = parent();
+
+        for coord in EXTREME_COORDS.iter() {
+            let point = position.abs_point_geometry(&geometry::point_from_coord(coord));
+
+            assert!(point[0].is_finite() && point[1].is_finite());
+            assert_eq!(position.rel_point_backend_coord(&point), *coord);
+        }
+    }
+
+    #[test]
+    fn it_saturates_points_outside_of_backend_coordinates() {
+        let position = parent();
+
+        assert_eq!(
+            position.rel_point_backend_coord(&[1e300, -1e300]),
+            (i32::MAX, i32::MAX)
+        );
+        assert_eq!(
+            position.rel_point_backend_coord(&[-1e300, 1e300]),
+            (i32::MIN, i32::MIN)
+        );
+        assert_eq!(
+            position.rel_point_backend_coord(&[ConrodScalar::INFINITY, ConrodScalar::NAN]),
+            (i32::MAX, 0)
+        );
     }
 }
@@ -4,95 +4,432 @@
 // Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
 // License: MIT
 
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
 use conrod_core::position::Scalar as ConrodScalar;
 use num_traits::identities::{One, Zero};
 
 type ShapeSplitterValue = ConrodScalar;
 type ShapeSplitterPoint = [ShapeSplitterValue; 2];
 
+// Notice: this matches the epsilon used by poly2tri orientation tests, so that points considered \
+//   as collinear upon triangulation get dropped from shapes beforehand.
+const SHAPE_SPLITTER_COLLINEAR_EPSILON: ShapeSplitterValue = 1e-12;
+
+// Notice: holes touching other shapes get their touching points moved by this distance (in \
+//   pixels), which is not visible on screen.
+const SHAPE_NESTER_SEPARATION: ShapeSplitterValue = 1e-3;
+
+// Notice: segments passing this close to a sweep event point (relative to its coordinates) are \
+//   considered as going through it, so that floating point errors on computed intersection \
+//   points do not make the sweep miss segments that intersect there.
+const SHAPE_SPLITTER_SWEEP_EPSILON: ShapeSplitterValue = 1e-9;
+
+#[derive(Default)]
 pub(crate) struct ShapeSplitter {
-    last_point: ShapeSplitterPoint,
     path_segments: Vec<[ShapeSplitterPoint; 2]>,
+    sweep_segments: Vec<[ShapeSplitterPoint; 2]>,
+    events: BinaryHeap<ShapeSplitterEvent>,
+    active: Vec<usize>,
+    starting: Vec<usize>,
+    crossing: Vec<usize>,
+    intersections: Vec<((usize, usize), ShapeSplitterPoint)>,
+    splits: Vec<(usize, ShapeSplitterPoint)>,
+    walk: Vec<ShapeSplitterPoint>,
+    walk_order: Vec<usize>,
+    walk_ids: Vec<usize>,
+    walk_positions: Vec<Option<usize>>,
+    walk_stack: Vec<usize>,
+    closed_shapes: Vec<Vec<ShapeSplitterPoint>>,
+    closed_shapes_count: usize,
 }
 
 impl ShapeSplitter {
-    pub(crate) fn try_from(path: &[ShapeSplitterPoint]) -> Result<Self, ()> {
+    // Notice: the splitter is meant to be re-used across calls, as all its buffers keep their \
+    //   allocated capacity around (this avoids allocating on every frame).
+    #[allow(clippy::float_cmp)]
+    pub(crate) fn load(&mut self, path: &[ShapeSplitterPoint]) -> Result<(), ()> {
         // Only proceed if we have enough points to form at least a triangle
         if path.len() >= 3 {
-            // Map all unique segments for the simplified path
-            let mut path_segments = Vec::new();
+            // Map all unique segments for the simplified path, including its closing segment
+            // Notice: the closing segment is skipped if the path is explicitly closed (ie. it \
+            //   ends on its starting point).
+            self.path_segments.clear();
 
-            for index in 0..(path.len() - 1) {
-                let (current_point, next_point) = (path[index], path[index + 1]);
+            for index in 0..path.len() {
+                let (current_point, next_point) = (path[index], path[(index + 1) % path.len()]);
 
-                path_segments.push([current_point, next_point]);
+                if current_point != next_point {
+                    self.path_segments.push([current_point, next_point]);
+                }
             }
 
-            Ok(Self {
-                last_point: path[path.len() - 1],
-                path_segments,
-            })
+            Ok(())
         } else {
             Err(())
         }
     }
 
     #[allow(clippy::float_cmp)]
-    pub(crate) fn collect(&mut self) -> Vec<Vec<ShapeSplitterPoint>> {
-        let (mut closed_shapes, mut current_shape_index) = (vec![Vec::new()], 0);
+    pub(crate) fn collect(&mut self) -> &mut [Vec<ShapeSplitterPoint>] {
+        self.intersections();
 
-        // Intersect each segment with all of its following segments, iteratively, and \
-        //   create paths for closed shapes.
-        for index in 0..self.path_segments.len() {
-            let path_segment = &self.path_segments[index];
+        let Self {
+            path_segments,
+            intersections,
+            splits,
+            walk,
+            walk_order,
+            walk_ids,
+            walk_positions,
+            walk_stack,
+            closed_shapes,
+            closed_shapes_count,
+            ..
+        } = self;
 
-            // Push opening (or current) point?
-            // Notice: check that previously pushed point does not match opening point, as in some \
-            //   cases the last pushed intersection point would be equal to the opening point to \
-            //   push there.
-            Self::append_point(&mut closed_shapes[current_shape_index], path_segment[0]);
+        // Split both segments of each intersection at the intersection point, in the order \
+        //   split points are met when walking each segment
+        splits.clear();
 
-            for sibling_index in (index + 1)..self.path_segments.len() {
-                let sibling_path_segment = &self.path_segments[sibling_index];
+        for ((first_index, second_index), point_intersect) in intersections.iter() {
+            splits.push((*first_index, *point_intersect));
+            splits.push((*second_index, *point_intersect));
+        }
 
-                // The lines are not directly connected? Proceed with intersection check.
-                if path_segment[1] != sibling_path_segment[0]
-                    && path_segment[0] != sibling_path_segment[1]
+        splits.sort_unstable_by(|(index, point), (other_index, other_point)| {
+            index.cmp(other_index).then_with(|| {
+                let start = &path_segments[*index][0];
+
+                Self::distance(start, point)
+                    .partial_cmp(&Self::distance(start, other_point))
+                    .unwrap_or(Ordering::Equal)
+            })
+        });
+
+        // Walk the path through all split points
+        // Notice: points are not walked twice in a row, as split points may match segment \
+        //   points (eg. when a path point touches another segment).
+        walk.clear();
+
+        let mut splits_cursor = 0;
+
+        for (index, path_segment) in path_segments.iter().enumerate() {
+            Self::walk_point(walk, path_segment[0]);
+
+            while let Some((_, point_split)) = splits
+                .get(splits_cursor)
+                .filter(|(segment, _)| *segment == index)
+            {
+                Self::walk_point(walk, *point_split);
+
+                splits_cursor += 1;
+            }
+        }
+
+        if walk.len() > 1 && walk[0] == walk[walk.len() - 1] {
+            walk.pop();
+        }
+
+        // Identify walked points, so that repeated points share the same identifier
+        walk_order.clear();
+        walk_order.extend(0..walk.len());
+
+        walk_order.sort_unstable_by(|index, other_index| {
+            walk[*index]
+                .partial_cmp(&walk[*other_index])
+                .unwrap_or(Ordering::Equal)
+        });
+
+        walk_ids.clear();
+        walk_ids.resize(walk.len(), 0);
+
+        for position in 1..walk_order.len() {
+            let (index, previous_index) = (walk_order[position], walk_order[position - 1]);
+
+            walk_ids[index] = if walk[index] == walk[previous_index] {
+                walk_ids[previous_index]
+            } else {
+                walk_ids[previous_index] + 1
+            };
+        }
+
+        // Create closed shapes, each time the walk gets back to a point it already went \
+        //   through (ie. an intersection point, or a point where the path touches itself, eg. \
+        //   keyhole shapes that are bridged to their outline), from the points walked in-between
+        walk_positions.clear();
+        walk_positions.resize(walk.len(), None);
+
+        walk_stack.clear();
+
+        *closed_shapes_count = 0;
+
+        for index in 0..walk.len() {
+            if let Some(position) = walk_positions[walk_ids[index]] {
+                Self::close_shape(
+                    closed_shapes,
+                    closed_shapes_count,
+                    walk,
+                    &walk_stack[position..],
+                );
+
+                for closed_index in walk_stack.drain((position + 1)..) {
+                    walk_positions[walk_ids[closed_index]] = None;
+                }
+            } else {
+                walk_positions[walk_ids[index]] = Some(walk_stack.len());
+                walk_stack.push(index);
+            }
+        }
+
+        // Close the remaining shape (ie. the one the path starts and ends on)
+        Self::close_shape(closed_shapes, closed_shapes_count, walk, walk_stack);
+
+        &mut closed_shapes[..*closed_shapes_count]
+    }
+
+    fn close_shape(
+        closed_shapes: &mut Vec<Vec<ShapeSplitterPoint>>,
+        closed_shapes_count: &mut usize,
+        walk: &[ShapeSplitterPoint],
+        walk_indices: &[usize],
+    ) {
+        // Re-use a previously allocated shape container if possible (its capacity is retained)
+        if *closed_shapes_count == closed_shapes.len() {
+            closed_shapes.push(Vec::new());
+        }
+
+        let closed_shape = &mut closed_shapes[*closed_shapes_count];
+
+        closed_shape.clear();
+        closed_shape.extend(walk_indices.iter().map(|index| walk[*index]));
+
+        Self::drop_collinear(closed_shape);
+
+        // Notice: shapes left with less than 3 points do not cover any area (eg. bridges going \
+        //   back and forth between two points), thus their container gets re-used.
+        if closed_shape.len() >= 3 {
+            *closed_shapes_count += 1;
+        }
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn intersections(&mut self) {
+        let Self {
+            path_segments,
+            sweep_segments,
+            events,
+            active,
+            starting,
+            crossing,
+            intersections,
+            ..
+        } = self;
+
+        // Sweep segments from left to right (Bentley-Ottmann), while maintaining the set of \
+        //   active segments ordered along the sweep line. Only segments that are neighbors in \
+        //   the active set, or that go through the same event point, get tested for \
+        //   intersection, which finds all intersections in O((n + k) log n) time.
+        // Notice: the active set is an ordered vector, where segments get found by binary search. \
+        //   Inserting and retiring segments moves the rest of the vector in memory, though the \
+        //   active set stays small on plot paths, as it only holds segments spanning the current \
+        //   sweep position.
+        // Notice: segments are oriented from their leftmost point (then bottommost point, for \
+        //   vertical segments), as this is the direction of the sweep.
+        sweep_segments.clear();
+        sweep_segments.extend(path_segments.iter().map(|segment| {
+            if Self::point_order(&segment[0], &segment[1]) == Ordering::Greater {
+                [segment[1], segment[0]]
+            } else {
+                *segment
+            }
+        }));
+
+        events.clear();
+
+        for (index, segment) in sweep_segments.iter().enumerate() {
+            events.push(ShapeSplitterEvent {
+                point: segment[0],
+                segment: Some(index),
+            });
+            events.push(ShapeSplitterEvent {
+                point: segment[1],
+                segment: None,
+            });
+        }
+
+        active.clear();
+        intersections.clear();
+
+        while let Some(event) = events.pop() {
+            let point = event.point;
+
+            // Gather all segments starting at the event point
+            starting.clear();
+            starting.extend(event.segment);
+
+            while events.peek().map_or(false, |next| next.point == point) {
+                if let Some(next) = events.pop() {
+                    starting.extend(next.segment);
+                }
+            }
+
+            // Find active segments going through the event point (they are contiguous in the \
+            //   active set, as it is ordered along the sweep line)
+            let epsilon = SHAPE_SPLITTER_SWEEP_EPSILON * (1.0 + point[1].abs());
+
+            let through_start = active.partition_point(|index| {
+                Self::sweep_y(&sweep_segments[*index], &point) < point[1] - epsilon
+            });
+            let through_end = through_start
+                + active[through_start..]
+                    .iter()
+                    .take_while(|index| {
+                        Self::sweep_y(&sweep_segments[**index], &point) <= point[1] + epsilon
+                    })
+                    .count();
+
+            // All segments going through the event point intersect there
+            for (position, index) in starting
+                .iter()
+                .chain(active[through_start..through_end].iter())
+                .enumerate()
+            {
+                for other_index in starting
+                    .iter()
+                    .chain(active[through_start..through_end].iter())
+                    .skip(position + 1)
                 {
-                    let intersection = Self::intersects(path_segment, sibling_path_segment);
+                    Self::test_pair(path_segments, intersections, *index, *other_index);
+                }
+            }
+
+            // Re-order segments going through the event point (as they cross there), along with \
+            //   segments starting there, while segments ending there get retired
+            crossing.clear();
+            crossing.extend(starting.iter().copied());
+            crossing.extend(
+                active
+                    .drain(through_start..through_end)
+                    .filter(|index| sweep_segments[*index][1] != point),
+            );
+
+            crossing.sort_unstable_by(|index, other_index| {
+                Self::sweep_slope(&sweep_segments[*index])
+                    .partial_cmp(&Self::sweep_slope(&sweep_segments[*other_index]))
+                    .unwrap_or(Ordering::Equal)
+                    .then(index.cmp(other_index))
+            });
 
-                    // An intersection has been found, the current shape can be closed and yielded
-                    if let Some(point_intersect) = intersection {
-                        // Close current closed shape at this point (ensure we are not pushing an \
-                        //   intersection equal to the starting point right after it)
-                        Self::append_point(
-                            &mut closed_shapes[current_shape_index],
-                            point_intersect,
-                        );
+            active.splice(through_start..through_start, crossing.iter().copied());
 
-                        // Start a new shape at this point (will be closed upon a future iteration)
-                        closed_shapes.push(vec![point_intersect]);
+            // Check new neighbors for intersections to come (ie. below and above re-ordered \
+            //   segments, or around the event point if no segment goes through it)
+            let (lower, upper) = (through_start, through_start + crossing.len());
 
-                        current_shape_index += 1;
+            for boundary in [lower, upper]
+                .iter()
+                .take(if lower == upper { 1 } else { 2 })
+            {
+                if *boundary > 0 && *boundary < active.len() {
+                    let (index, other_index) = (active[*boundary - 1], active[*boundary]);
+
+                    if Self::test_pair(path_segments, intersections, index, other_index) {
+                        if let Some(point_intersect) =
+                            Self::intersection(&sweep_segments[index], &sweep_segments[other_index])
+                                .filter(|point_intersect| {
+                                    Self::point_order(point_intersect, &point) == Ordering::Greater
+                                })
+                        {
+                            events.push(ShapeSplitterEvent {
+                                point: point_intersect,
+                                segment: None,
+                            });
+                        }
                     }
                 }
             }
         }
 
-        // Close the first shape with the last point from the original shape?
-        // Notice: points shall not be repeated, hence why there is a check that we are not \
-        //   closing the shape with either its starting point, or already-there ending point.
-        if !closed_shapes.is_empty() {
-            Self::append_point(&mut closed_shapes[0], self.last_point);
+        // Order intersections as they are met when walking the path
+        // Notice: a given pair of segments may have been tested multiple times.
+        intersections.sort_unstable_by_key(|(segments, _)| *segments);
+        intersections.dedup_by_key(|(segments, _)| *segments);
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn test_pair(
+        path_segments: &[[ShapeSplitterPoint; 2]],
+        intersections: &mut Vec<((usize, usize), ShapeSplitterPoint)>,
+        index: usize,
+        other_index: usize,
+    ) -> bool {
+        // Notice: always intersect the segments in path order, so that the intersection point \
+        //   is computed the exact same way for a given pair.
+        let (first_index, second_index) = (index.min(other_index), index.max(other_index));
+
+        let (path_segment, sibling_path_segment) =
+            (&path_segments[first_index], &path_segments[second_index]);
+
+        // The lines are not directly connected? Proceed with intersection check.
+        if path_segment[1] != sibling_path_segment[0] && path_segment[0] != sibling_path_segment[1]
+        {
+            if let Some(point_intersect) = Self::intersects(path_segment, sibling_path_segment) {
+                intersections.push(((first_index, second_index), point_intersect));
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline(always)]
+    fn point_order(point: &ShapeSplitterPoint, other: &ShapeSplitterPoint) -> Ordering {
+        point.partial_cmp(other).unwrap_or(Ordering::Equal)
+    }
+
+    #[inline(always)]
+    #[allow(clippy::float_cmp)]
+    fn sweep_y(
+        segment: &[ShapeSplitterPoint; 2],
+        point: &ShapeSplitterPoint,
+    ) -> ShapeSplitterValue {
+        let (start, end) = (segment[0], segment[1]);
+
+        if start[0] == end[0] {
+            // Vertical segments lie at the sweep point, while it moves along them
+            point[1].max(start[1]).min(end[1])
+        } else {
+            start[1] + (point[0] - start[0]) * (end[1] - start[1]) / (end[0] - start[0])
         }
+    }
 
-        closed_shapes
+    #[inline(always)]
+    #[allow(clippy::float_cmp)]
+    fn sweep_slope(segment: &[ShapeSplitterPoint; 2]) -> ShapeSplitterValue {
+        let (start, end) = (segment[0], segment[1]);
+
+        if start[0] == end[0] {
+            ShapeSplitterValue::INFINITY
+        } else {
+            (end[1] - start[1]) / (end[0] - start[0])
+        }
     }
 
-    #[allow(clippy::many_single_char_names, clippy::float_cmp)]
     fn intersects(
         line: &[ShapeSplitterPoint; 2],
         other: &[ShapeSplitterPoint; 2],
+    ) -> Option<ShapeSplitterPoint> {
+        // Return intersection point coordinates (rounded as to avoid floating point errors)
+        Self::intersection(line, other).map(|point| [point[0].round(), point[1].round()])
+    }
+
+    #[allow(clippy::many_single_char_names, clippy::float_cmp)]
+    fn intersection(
+        line: &[ShapeSplitterPoint; 2],
+        other: &[ShapeSplitterPoint; 2],
     ) -> Option<ShapeSplitterPoint> {
         // Adapted from: https://github.com/ucarion/line_intersection/blob/master/src/lib.rs#L108
 
@@ -117,8 +454,7 @@ impl ShapeSplitter {
             let u_in_range = ShapeSplitterValue::zero() <= u && u <= ShapeSplitterValue::one();
 
             if t_in_range && u_in_range {
-                // Return intersection point coordinates (rounded as to avoid floating point errors)
-                Some([(p[0] + t * r[0]).round(), (p[1] + t * r[1]).round()])
+                Some([p[0] + t * r[0], p[1] + t * r[1]])
             } else {
                 None
             }
@@ -135,14 +471,600 @@ impl ShapeSplitter {
         [point[0] / other, point[1] / other]
     }
 
+    fn drop_collinear(shape: &mut Vec<ShapeSplitterPoint>) {
+        // Drop points that lie on a straight line with their neighbors (ie. points in the middle \
+        //   of an edge, or spikes going back and forth), as they do not cover any area, and \
+        //   the triangulation library rejects them
+        let is_collinear = |previous: &ShapeSplitterPoint,
+                            point: &ShapeSplitterPoint,
+                            next: &ShapeSplitterPoint| {
+            Self::point_cross(
+                &[point[0] - previous[0], point[1] - previous[1]],
+                &[next[0] - previous[0], next[1] - previous[1]],
+            )
+            .abs()
+                < SHAPE_SPLITTER_COLLINEAR_EPSILON
+        };
+
+        let mut kept = 0;
+
+        for index in 0..shape.len() {
+            shape[kept] = shape[index];
+            kept += 1;
+
+            while kept >= 3 && is_collinear(&shape[kept - 3], &shape[kept - 2], &shape[kept - 1]) {
+                shape[kept - 2] = shape[kept - 1];
+                kept -= 1;
+            }
+        }
+
+        shape.truncate(kept);
+
+        // Also drop collinear points where the shape wraps around
+        while shape.len() >= 3 {
+            let size = shape.len();
+
+            if is_collinear(&shape[size - 2], &shape[size - 1], &shape[0]) {
+                shape.pop();
+            } else if is_collinear(&shape[size - 1], &shape[0], &shape[1]) {
+                shape.remove(0);
+            } else {
+                break;
+            }
+        }
+    }
+
     #[inline(always)]
     #[allow(clippy::float_cmp)]
-    fn append_point(container: &mut Vec<ShapeSplitterPoint>, point: ShapeSplitterPoint) {
-        let container_size = container.len();
+    fn walk_point(walk: &mut Vec<ShapeSplitterPoint>, point: ShapeSplitterPoint) {
+        if walk.last() != Some(&point) {
+            walk.push(point);
+        }
+    }
 
-        if container_size == 0 || (container[container_size - 1] != point && container[0] != point)
-        {
-            container.push(point);
+    #[inline(always)]
+    fn distance(point: &ShapeSplitterPoint, other: &ShapeSplitterPoint) -> ShapeSplitterValue {
+        (other[0] - point[0]).hypot(other[1] - point[1])
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ShapeSplitterEvent {
+    point: ShapeSplitterPoint,
+    // The segment starting at this point (if any)
+    segment: Option<usize>,
+}
+
+impl PartialEq for ShapeSplitterEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ShapeSplitterEvent {}
+
+impl PartialOrd for ShapeSplitterEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ShapeSplitterEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Notice: the order is reversed, as binary heaps pop their greatest element first, while \
+        //   events must be popped from left to right.
+        ShapeSplitter::point_order(&other.point, &self.point)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ShapeNester {
+    bounds: Vec<[ShapeSplitterPoint; 2]>,
+    areas: Vec<ShapeSplitterValue>,
+    containers: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    shared: Vec<(ShapeSplitterPoint, usize, usize)>,
+}
+
+impl ShapeNester {
+    /// Group closed shapes into outlines and holes, following the even-odd rule
+    ///
+    /// Shapes that lie within another shape become holes of their direct container, while \
+    ///   shapes nested within holes become outlines again (eg. an island within a donut). Shapes \
+    ///   may touch their container (eg. on the points they were split at), in which case the \
+    ///   touching points of holes get moved inside the hole by a sub-pixel distance, as the \
+    ///   triangulation library does not support holes touching the outline. Shapes with less \
+    ///   than 3 points are ignored.
+    pub(crate) fn collect(&mut self, shapes: &mut [Vec<ShapeSplitterPoint>]) {
+        let Self {
+            bounds,
+            areas,
+            containers,
+            depths,
+            ..
+        } = self;
+
+        containers.clear();
+        containers.resize(shapes.len(), None);
+
+        depths.clear();
+        depths.resize(shapes.len(), 0);
+
+        // Fast path: nothing can be nested (this is the most common case)
+        if shapes.iter().filter(|shape| shape.len() >= 3).count() < 2 {
+            return;
         }
+
+        bounds.clear();
+        bounds.extend(shapes.iter().map(|shape| Self::bounds(shape)));
+
+        areas.clear();
+        areas.extend(shapes.iter().map(|shape| Self::area(shape)));
+
+        // Find the direct container of each shape (ie. the smallest shape that contains it)
+        for (index, shape) in shapes.iter().enumerate() {
+            if shape.len() < 3 {
+                continue;
+            }
+
+            for (other_index, other) in shapes.iter().enumerate() {
+                if index != other_index
+                    && other.len() >= 3
+                    && areas[other_index].abs() > areas[index].abs()
+                    && containers[index].map_or(true, |container| {
+                        areas[other_index].abs() < areas[container].abs()
+                    })
+                    && Self::bounds_contain(&bounds[other_index], &bounds[index])
+                    && Self::contains_shape(other, shape)
+                {
+                    containers[index] = Some(other_index);
+                }
+            }
+        }
+
+        // Compute nesting depths (holes have odd depths, while outlines have even depths)
+        for (index, depth) in depths.iter_mut().enumerate() {
+            let mut current = index;
+
+            while let Some(container) = containers[current] {
+                *depth += 1;
+                current = container;
+            }
+        }
+
+        self.separate_holes(shapes);
+    }
+
+    /// Iterate on the outlines found upon the last collect, as shape indices
+    pub(crate) fn outlines<'a>(
+        &'a self,
+        shapes: &'a [Vec<ShapeSplitterPoint>],
+    ) -> impl Iterator<Item = usize> + 'a {
+        (0..shapes.len()).filter(move |index| shapes[*index].len() >= 3 && !self.is_hole(*index))
+    }
+
+    /// Iterate on the holes of an outline found upon the last collect, as shape indices
+    pub(crate) fn holes(&self, outline: usize) -> impl Iterator<Item = usize> + Clone + '_ {
+        (0..self.containers.len())
+            .filter(move |index| self.containers[*index] == Some(outline) && self.is_hole(*index))
+    }
+
+    #[inline(always)]
+    fn is_hole(&self, index: usize) -> bool {
+        self.depths[index] % 2 == 1
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn separate_holes(&mut self, shapes: &mut [Vec<ShapeSplitterPoint>]) {
+        let Self {
+            areas,
+            depths,
+            shared,
+            ..
+        } = self;
+
+        // List all points of holes and of their containers, ordered by position, so that \
+        //   points shared by multiple shapes follow each other
+        shared.clear();
+
+        for (index, shape) in shapes.iter().enumerate() {
+            if shape.len() >= 3 {
+                shared.extend(
+                    shape
+                        .iter()
+                        .enumerate()
+                        .map(|(point_index, point)| (*point, index, point_index)),
+                );
+            }
+        }
+
+        shared.sort_unstable_by(|(point, ..), (other_point, ..)| {
+            point.partial_cmp(other_point).unwrap_or(Ordering::Equal)
+        });
+
+        // Move shared points of holes inside the hole they belong to, along the bisector of \
+        //   their edges
+        for position in 0..shared.len() {
+            let (point, index, point_index) = shared[position];
+
+            let is_shared = (position > 0 && shared[position - 1].0 == point)
+                || (position + 1 < shared.len() && shared[position + 1].0 == point);
+
+            if is_shared && depths[index] % 2 == 1 {
+                let shape = &mut shapes[index];
+
+                let (previous, next) = (
+                    shape[(point_index + shape.len() - 1) % shape.len()],
+                    shape[(point_index + 1) % shape.len()],
+                );
+
+                let (to_previous, to_next) =
+                    (Self::unit(&point, &previous), Self::unit(&point, &next));
+
+                let mut bisector = [to_previous[0] + to_next[0], to_previous[1] + to_next[1]];
+
+                // The interior of reflex points lies on the other side of their bisector
+                if Self::cross(&to_next, &to_previous).signum() != areas[index].signum() {
+                    bisector = [-bisector[0], -bisector[1]];
+                }
+
+                let bisector = Self::unit(&[0.0, 0.0], &bisector);
+
+                shape[point_index] = [
+                    point[0] + bisector[0] * SHAPE_NESTER_SEPARATION,
+                    point[1] + bisector[1] * SHAPE_NESTER_SEPARATION,
+                ];
+            }
+        }
+    }
+
+    fn bounds(shape: &[ShapeSplitterPoint]) -> [ShapeSplitterPoint; 2] {
+        shape.iter().fold(
+            [
+                [ShapeSplitterValue::INFINITY, ShapeSplitterValue::INFINITY],
+                [
+                    ShapeSplitterValue::NEG_INFINITY,
+                    ShapeSplitterValue::NEG_INFINITY,
+                ],
+            ],
+            |bounds, point| {
+                [
+                    [bounds[0][0].min(point[0]), bounds[0][1].min(point[1])],
+                    [bounds[1][0].max(point[0]), bounds[1][1].max(point[1])],
+                ]
+            },
+        )
+    }
+
+    #[inline(always)]
+    fn bounds_contain(bounds: &[ShapeSplitterPoint; 2], other: &[ShapeSplitterPoint; 2]) -> bool {
+        bounds[0][0] <= other[0][0]
+            && bounds[0][1] <= other[0][1]
+            && bounds[1][0] >= other[1][0]
+            && bounds[1][1] >= other[1][1]
+    }
+
+    fn area(shape: &[ShapeSplitterPoint]) -> ShapeSplitterValue {
+        // Shoelace formula (shapes are implicitly closed, and the area sign gives their winding)
+        let mut area = ShapeSplitterValue::zero();
+
+        for index in 0..shape.len() {
+            let (point, next_point) = (shape[index], shape[(index + 1) % shape.len()]);
+
+            area += point[0] * next_point[1] - next_point[0] * point[1];
+        }
+
+        area / 2.0
+    }
+
+    fn contains_shape(shape: &[ShapeSplitterPoint], other: &[ShapeSplitterPoint]) -> bool {
+        // Shapes do not cross each other (as they were split at their intersections), though \
+        //   they may touch each other. Hence, the first point of the other shape that does not \
+        //   lie on the shape outline tells whether the other shape lies within the shape. If all \
+        //   points lie on the outline, edge middle points are checked instead.
+        let middle_points = (0..other.len()).map(|index| {
+            let (point, next_point) = (other[index], other[(index + 1) % other.len()]);
+
+            [
+                (point[0] + next_point[0]) / 2.0,
+                (point[1] + next_point[1]) / 2.0,
+            ]
+        });
+
+        other
+            .iter()
+            .copied()
+            .chain(middle_points)
+            .find_map(|point| Self::contains(shape, &point))
+            .unwrap_or(false)
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn contains(shape: &[ShapeSplitterPoint], point: &ShapeSplitterPoint) -> Option<bool> {
+        // Ray casting, where points lying on an edge are neither inside nor outside
+        let mut inside = false;
+
+        for index in 0..shape.len() {
+            let (start, end) = (shape[index], shape[(index + 1) % shape.len()]);
+            let edge = [end[0] - start[0], end[1] - start[1]];
+
+            let cross = Self::cross(&edge, &[point[0] - start[0], point[1] - start[1]]);
+
+            if cross == ShapeSplitterValue::zero()
+                && point[0] >= start[0].min(end[0])
+                && point[0] <= start[0].max(end[0])
+                && point[1] >= start[1].min(end[1])
+                && point[1] <= start[1].max(end[1])
+            {
+                return None;
+            }
+
+            if (start[1] > point[1]) != (end[1] > point[1])
+                && point[0] < start[0] + (point[1] - start[1]) / edge[1] * edge[0]
+            {
+                inside = !inside;
+            }
+        }
+
+        Some(inside)
+    }
+
+    #[inline(always)]
+    fn cross(point: &ShapeSplitterPoint, other: &ShapeSplitterPoint) -> ShapeSplitterValue {
+        point[0] * other[1] - point[1] * other[0]
+    }
+
+    #[inline(always)]
+    fn unit(point: &ShapeSplitterPoint, other: &ShapeSplitterPoint) -> ShapeSplitterPoint {
+        let delta = [other[0] - point[0], other[1] - point[1]];
+        let length = delta[0].hypot(delta[1]);
+
+        [delta[0] / length, delta[1] / length]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::triangulate::Triangulator;
+
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, max: u64) -> ShapeSplitterValue {
+            // Xorshift generator (seeded, so that test runs are reproducible)
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            (self.0 % max) as ShapeSplitterValue
+        }
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn intersections_quadratic(
+        path_segments: &[[ShapeSplitterPoint; 2]],
+    ) -> Vec<((usize, usize), ShapeSplitterPoint)> {
+        // Reference implementation, which intersects each segment with all of its following \
+        //   segments (this is how intersections were found before the sweep got introduced)
+        let mut intersections = Vec::new();
+
+        for (index, path_segment) in path_segments.iter().enumerate() {
+            for (sibling_index, sibling_path_segment) in
+                path_segments.iter().enumerate().skip(index + 1)
+            {
+                if path_segment[1] != sibling_path_segment[0]
+                    && path_segment[0] != sibling_path_segment[1]
+                {
+                    if let Some(point_intersect) =
+                        ShapeSplitter::intersects(path_segment, sibling_path_segment)
+                    {
+                        intersections.push(((index, sibling_index), point_intersect));
+                    }
+                }
+            }
+        }
+
+        intersections
+    }
+
+    fn assert_same_intersections(splitter: &mut ShapeSplitter, path: &[ShapeSplitterPoint]) {
+        splitter.load(path).unwrap();
+        splitter.collect();
+
+        assert_eq!(
+            splitter.intersections,
+            intersections_quadratic(&splitter.path_segments)
+        );
+    }
+
+    fn filled_area(path: &[ShapeSplitterPoint]) -> ShapeSplitterValue {
+        let (mut splitter, mut nester, mut triangulator) = (
+            ShapeSplitter::default(),
+            ShapeNester::default(),
+            Triangulator::default(),
+        );
+
+        splitter.load(path).unwrap();
+
+        let shapes = splitter.collect();
+
+        nester.collect(shapes);
+
+        nester
+            .outlines(shapes)
+            .map(|outline| {
+                let triangles = triangulator
+                    .triangulate(
+                        shapes[outline].iter(),
+                        nester.holes(outline).map(|hole| shapes[hole].as_slice()),
+                    )
+                    .unwrap();
+
+                (0..triangles.size())
+                    .map(|index| {
+                        ShapeNester::area(&triangles.get_triangle(index).unwrap().points).abs()
+                    })
+                    .sum::<ShapeSplitterValue>()
+            })
+            .sum()
+    }
+
+    fn square(origin: ShapeSplitterValue, size: ShapeSplitterValue) -> Vec<ShapeSplitterPoint> {
+        vec![
+            [origin, origin],
+            [origin + size, origin],
+            [origin + size, origin + size],
+            [origin, origin + size],
+        ]
+    }
+
+    #[test]
+    fn it_finds_the_same_intersections_as_quadratic_on_random_paths() {
+        let (mut random, mut splitter) = (Random(0x2545f4914f6cdd1d), ShapeSplitter::default());
+
+        for _ in 0..500 {
+            let length = 3 + random.next(60) as usize;
+
+            let path: Vec<_> = (0..length)
+                .map(|_| [random.next(100), random.next(100)])
+                .collect();
+
+            assert_same_intersections(&mut splitter, &path);
+        }
+    }
+
+    #[test]
+    fn it_finds_the_same_intersections_as_quadratic_on_random_series() {
+        let (mut random, mut splitter) = (Random(0x9e3779b97f4a7c15), ShapeSplitter::default());
+
+        for _ in 0..50 {
+            // Area series crossing their baseline, with many samples per pixel column
+            let samples = 100 + random.next(1000) as usize;
+
+            let mut path: Vec<_> = (0..samples)
+                .map(|index| [(index / 4) as ShapeSplitterValue, random.next(200)])
+                .collect();
+
+            path.push([((samples - 1) / 4) as ShapeSplitterValue, 100.0]);
+            path.push([0.0, 100.0]);
+
+            assert_same_intersections(&mut splitter, &path);
+        }
+    }
+
+    #[test]
+    fn it_finds_the_same_intersections_as_quadratic_on_degenerate_paths() {
+        let (mut random, mut splitter) = (Random(0xd1b54a32d192ed03), ShapeSplitter::default());
+
+        for _ in 0..5_000 {
+            // Random paths on a small grid, which makes vertical segments, overlapping segments \
+            //   and many segments going through the same point frequent
+            let length = 3 + random.next(20) as usize;
+
+            let path: Vec<_> = (0..length)
+                .map(|_| [random.next(5), random.next(5)])
+                .collect();
+
+            assert_same_intersections(&mut splitter, &path);
+        }
+    }
+
+    #[test]
+    fn it_finds_intersections_of_segments_going_through_the_same_point() {
+        let mut splitter = ShapeSplitter::default();
+
+        // Star, where three segments (including a vertical one) cross in its center
+        let path = [
+            [0.0, 0.0],
+            [20.0, 20.0],
+            [20.0, 0.0],
+            [0.0, 20.0],
+            [10.0, 0.0],
+            [10.0, 20.0],
+        ];
+
+        assert_same_intersections(&mut splitter, &path);
+
+        for segments in [(0, 2), (0, 4), (2, 4)] {
+            assert!(splitter.intersections.contains(&(segments, [10.0, 10.0])));
+        }
+    }
+
+    #[test]
+    fn it_splits_crossing_paths_into_shapes() {
+        let mut splitter = ShapeSplitter::default();
+
+        // Bowtie, crossing itself in its center
+        splitter
+            .load(&[[0.0, 0.0], [10.0, 10.0], [10.0, 0.0], [0.0, 10.0]])
+            .unwrap();
+
+        assert_eq!(
+            splitter.collect(),
+            &[
+                vec![[5.0, 5.0], [10.0, 10.0], [10.0, 0.0]],
+                vec![[0.0, 0.0], [5.0, 5.0], [0.0, 10.0]]
+            ]
+        );
+    }
+
+    #[test]
+    fn it_fills_donuts_bridged_to_their_outline() {
+        // Outline, followed by a bridge to the inner ring, going back to the outline
+        let mut path = square(0.0, 100.0);
+
+        path.push([0.0, 0.0]);
+        path.extend(square(20.0, 60.0));
+        path.push([20.0, 20.0]);
+
+        assert!((filled_area(&path) - 6400.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn it_fills_keyholes() {
+        // Outline, with a bridge at mid-height going back and forth to the inner ring
+        let path = [
+            [0.0, 0.0],
+            [100.0, 0.0],
+            [100.0, 50.0],
+            [70.0, 50.0],
+            [70.0, 30.0],
+            [30.0, 30.0],
+            [30.0, 70.0],
+            [70.0, 70.0],
+            [70.0, 50.0],
+            [100.0, 50.0],
+            [100.0, 100.0],
+            [0.0, 100.0],
+        ];
+
+        assert!((filled_area(&path) - 8400.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn it_fills_holes_touching_their_outline() {
+        // Outline, followed by a triangle hole touching its corner
+        let mut path = square(0.0, 100.0);
+
+        path.extend([[0.0, 0.0], [40.0, 20.0], [20.0, 40.0]]);
+
+        // Notice: the touching point of the hole gets moved by a sub-pixel distance
+        assert!((filled_area(&path) - (10000.0 - 600.0)).abs() < 0.1);
+    }
+
+    #[test]
+    fn it_fills_islands_within_donuts() {
+        let mut path = square(0.0, 100.0);
+
+        path.push([0.0, 0.0]);
+        path.extend(square(20.0, 60.0));
+        path.push([20.0, 20.0]);
+        path.push([0.0, 0.0]);
+        path.extend(square(40.0, 20.0));
+        path.push([40.0, 40.0]);
+
+        assert!((filled_area(&path) - (6400.0 + 400.0)).abs() < 1e-6);
     }
 }
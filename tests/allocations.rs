@@ -0,0 +1,230 @@
+// plotters-conrod
+//
+// Conrod backend for Plotters
+// Copyright: 2020, Valerian Saliou <valerian@valeriansaliou.name>
+// License: MIT
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use conrod_core::{self as conrod, widget_ids, Positionable, Sizeable, Widget};
+use plotters_backend::{BackendColor, BackendCoord, BackendStyle, DrawingBackend};
+use plotters_conrod::{ConrodBackend, ConrodBackendReusableGraph};
+
+const PLOT_SIZE: (u32, u32) = (800, 480);
+
+struct CountingAllocator;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// Notice: tests run in parallel threads, while allocations are counted globally, therefore \
+//   frames are drawn one at a time.
+static FRAME_LOCK: Mutex<()> = Mutex::new(());
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.load(Ordering::SeqCst) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.load(Ordering::SeqCst) {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+widget_ids!(struct Ids { parent });
+
+struct Style(BackendColor, u32);
+
+impl BackendStyle for Style {
+    fn color(&self) -> BackendColor {
+        self.0
+    }
+
+    fn stroke_width(&self) -> u32 {
+        self.1
+    }
+}
+
+struct FrameCount {
+    allocations: usize,
+    widgets: usize,
+}
+
+fn style() -> Style {
+    Style(
+        BackendColor {
+            alpha: 1.0,
+            rgb: (0, 0, 255),
+        },
+        2,
+    )
+}
+
+fn series() -> Vec<BackendCoord> {
+    (0..5_000)
+        .map(|index| {
+            (
+                index * PLOT_SIZE.0 as i32 / 5_000,
+                240 + ((index as f64 * 0.05).sin() * 150.0) as i32,
+            )
+        })
+        .collect()
+}
+
+fn area() -> Vec<BackendCoord> {
+    let mut area = series();
+
+    area.push((PLOT_SIZE.0 as i32 - 1, 400));
+    area.push((0, 400));
+
+    area
+}
+
+fn draw_frames<F>(frames: usize, draw: F) -> Vec<FrameCount>
+where
+    F: Fn(&mut ConrodBackend),
+{
+    let _lock = FRAME_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let mut ui = conrod::UiBuilder::new([PLOT_SIZE.0 as f64, PLOT_SIZE.1 as f64]).build();
+
+    let ids = Ids::new(ui.widget_id_generator());
+    let font = ui
+        .fonts
+        .insert_from_file(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/fonts/notosans-regular.ttf"),
+        )
+        .unwrap();
+
+    let mut graph = ConrodBackendReusableGraph::build();
+
+    (0..frames)
+        .map(|_| {
+            let ui = &mut ui.set_widgets();
+
+            conrod::widget::Canvas::new()
+                .w_h(PLOT_SIZE.0 as f64, PLOT_SIZE.1 as f64)
+                .middle()
+                .set(ids.parent, ui);
+
+            // Count allocations from the backend creation, up to its drop (recording and hit \
+            //   testing are disabled)
+            ALLOCATIONS.store(0, Ordering::SeqCst);
+            COUNTING.store(true, Ordering::SeqCst);
+
+            {
+                let mut backend = ConrodBackend::new(ui, PLOT_SIZE, ids.parent, font, &mut graph)
+                    .simplify(0.5, true)
+                    .decimate(true)
+                    .stroke_polygons(true);
+
+                draw(&mut backend);
+
+                backend.present().unwrap();
+            }
+
+            COUNTING.store(false, Ordering::SeqCst);
+
+            FrameCount {
+                allocations: ALLOCATIONS.load(Ordering::SeqCst),
+                widgets: graph.rendered().widgets,
+            }
+        })
+        .collect()
+}
+
+fn assert_allocates_for_widgets_only<F>(name: &str, draw: F) -> usize
+where
+    F: Fn(&mut ConrodBackend),
+{
+    // Notice: the first frames grow the graph scratch buffers, as well as Conrod internal \
+    //   caches, therefore only the last frame is checked.
+    let frames = draw_frames(3, draw);
+    let frame = &frames[frames.len() - 1];
+
+    // Conrod allocates exactly once when updating each widget that owns its points (ie. \
+    //   triangles and point path widgets, as drawn here), therefore any allocation beyond the \
+    //   number of widgets that were set would be made by the backend itself.
+    assert!(frame.widgets > 0, "{} frame sets no widget", name);
+
+    assert_eq!(
+        frame.allocations, frame.widgets,
+        "{} frame made {} allocations for {} widgets",
+        name, frame.allocations, frame.widgets
+    );
+
+    frame.widgets
+}
+
+#[test]
+fn it_only_allocates_for_widgets_when_drawing_rects() {
+    assert_allocates_for_widgets_only("rect", |backend| {
+        for index in 0..50 {
+            backend
+                .draw_rect((index * 10, 10), (index * 10 + 8, 100), &style(), true)
+                .unwrap();
+            backend
+                .draw_rect((index * 10, 200), (index * 10 + 8, 300), &style(), false)
+                .unwrap();
+        }
+    });
+}
+
+#[test]
+fn it_only_allocates_for_widgets_when_drawing_paths() {
+    let series = series();
+
+    assert_allocates_for_widgets_only("path", |backend| {
+        backend.draw_path(series.iter().copied(), &style()).unwrap();
+    });
+}
+
+#[test]
+fn it_only_allocates_for_widgets_when_filling_polygons() {
+    let area = area();
+
+    let widgets = assert_allocates_for_widgets_only("fill", |backend| {
+        backend
+            .fill_polygon(area.iter().copied(), &style())
+            .unwrap();
+    });
+
+    // Fill triangles are batched along with the outline triangles (as they share their color)
+    assert_eq!(widgets, 1);
+}
+
+#[test]
+fn it_only_allocates_for_widgets_when_drawing_frames() {
+    let (series, area) = (series(), area());
+
+    assert_allocates_for_widgets_only("mixed", |backend| {
+        backend.draw_path(series.iter().copied(), &style()).unwrap();
+        backend
+            .fill_polygon(area.iter().copied(), &style())
+            .unwrap();
+
+        for index in 0..50 {
+            backend
+                .draw_rect((index * 10, 10), (index * 10 + 8, 100), &style(), true)
+                .unwrap();
+        }
+    });
+}